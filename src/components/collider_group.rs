@@ -1,33 +1,32 @@
-use super::ship;
+use super::faction::{Faction, Factions};
 use bevy_rapier3d::prelude::*;
 
-pub trait AsCollisionGroups {
-    fn as_collision_groups(&self) -> CollisionGroups;
+// Each faction's ships sit in an even group and its cannon balls in the odd group after it
+fn ship_group(faction: Faction) -> Group {
+    Group::from_bits_truncate(1u32.checked_shl(faction.index() as u32 * 2).unwrap_or(0))
 }
 
-const PLAYER_SHIP: Group = Group::GROUP_1;
-const PLAYER_CANNON: Group = Group::GROUP_2;
-const ENEMY_SHIP: Group = Group::GROUP_3;
-const ENEMY_CANNON: Group = Group::GROUP_4;
+fn cannon_group(faction: Faction) -> Group {
+    Group::from_bits_truncate(
+        1u32.checked_shl(faction.index() as u32 * 2 + 1)
+            .unwrap_or(0),
+    )
+}
 
-impl AsCollisionGroups for ship::cannons::CannonMarker {
-    fn as_collision_groups(&self) -> CollisionGroups {
-        match self {
-            ship::cannons::CannonMarker::Player => {
-                CollisionGroups::new(PLAYER_CANNON, Group::ALL ^ PLAYER_SHIP ^ PLAYER_CANNON)
-            }
-            ship::cannons::CannonMarker::Enemy => {
-                CollisionGroups::new(ENEMY_CANNON, Group::ALL ^ ENEMY_SHIP ^ ENEMY_CANNON)
-            }
-        }
-    }
+/// Ships run into anything, whichever side it's on.
+pub fn ship_collision_groups(faction: Faction) -> CollisionGroups {
+    CollisionGroups::new(ship_group(faction), Group::ALL)
 }
 
-impl AsCollisionGroups for ship::ShipMarker {
-    fn as_collision_groups(&self) -> CollisionGroups {
-        match self {
-            ship::ShipMarker::Player => CollisionGroups::new(PLAYER_SHIP, Group::ALL),
-            ship::ShipMarker::Enemy => CollisionGroups::new(ENEMY_SHIP, Group::ALL),
-        }
-    }
+/// Cannon balls pass through their own side's balls, and through the ships of any faction allied with theirs.
+/// Neutral ships can still be caught by a stray broadside.
+pub fn cannon_collision_groups(faction: Faction, factions: &Factions) -> CollisionGroups {
+    let friendly_ships = factions
+        .iter()
+        .filter(|other| factions.is_allied(faction, *other))
+        .fold(Group::NONE, |groups, other| groups | ship_group(other));
+    CollisionGroups::new(
+        cannon_group(faction),
+        Group::ALL ^ cannon_group(faction) ^ friendly_ships,
+    )
 }
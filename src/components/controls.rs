@@ -0,0 +1,426 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+
+const CONTROLS_DIRECTORY: &str = "config";
+const CONTROLS_FILE: &str = "config/controls.ron";
+// Stick deflection ignored to stop worn sticks from drifting the ship or camera
+const STICK_DEADZONE: f32 = 0.15;
+
+/// Everything the player can ask of their ship, independent of the key or button bound to it.
+/// Lowering sails lets out more canvas and speeds the ship up, raising them slows it down.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Action {
+    RaiseSails,
+    LowerSails,
+    TurnPort,
+    TurnStarboard,
+    FirePort,
+    FireStarboard,
+    AimPort,
+    AimStarboard,
+    ElevateCannons,
+    DepressCannons,
+    NextAmmo,
+    Repair,
+    Board,
+    SwitchShip,
+    FormUp,
+    Attack,
+    Hold,
+    Retreat,
+    FleetMenu,
+    Spectate,
+    Pause,
+}
+
+impl Action {
+    pub const ALL: [Action; 21] = [
+        Action::RaiseSails,
+        Action::LowerSails,
+        Action::TurnPort,
+        Action::TurnStarboard,
+        Action::FirePort,
+        Action::FireStarboard,
+        Action::AimPort,
+        Action::AimStarboard,
+        Action::ElevateCannons,
+        Action::DepressCannons,
+        Action::NextAmmo,
+        Action::Repair,
+        Action::Board,
+        Action::SwitchShip,
+        Action::FormUp,
+        Action::Attack,
+        Action::Hold,
+        Action::Retreat,
+        Action::FleetMenu,
+        Action::Spectate,
+        Action::Pause,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::RaiseSails => "Raise Sails",
+            Action::LowerSails => "Lower Sails",
+            Action::TurnPort => "Turn Port",
+            Action::TurnStarboard => "Turn Starboard",
+            Action::FirePort => "Fire Port",
+            Action::FireStarboard => "Fire Starboard",
+            Action::AimPort => "Aim Port",
+            Action::AimStarboard => "Aim Starboard",
+            Action::ElevateCannons => "Elevate Cannons",
+            Action::DepressCannons => "Depress Cannons",
+            Action::NextAmmo => "Next Ammo",
+            Action::Repair => "Repair",
+            Action::Board => "Board",
+            Action::SwitchShip => "Switch Ship",
+            Action::FormUp => "Fleet Form Up",
+            Action::Attack => "Fleet Attack",
+            Action::Hold => "Fleet Hold",
+            Action::Retreat => "Fleet Retreat",
+            Action::FleetMenu => "Fleet Menu",
+            Action::Spectate => "Spectate",
+            Action::Pause => "Pause",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButtonType),
+}
+
+impl Binding {
+    pub fn label(&self) -> String {
+        match self {
+            Binding::Key(key) => format!("{:?}", key),
+            Binding::Mouse(button) => format!("Mouse {:?}", button),
+            Binding::Gamepad(button) => format!("Pad {:?}", button),
+        }
+    }
+
+    /// Keyboard and mouse share a device, so an action can have one binding there and one on the gamepad.
+    fn is_gamepad(&self) -> bool {
+        matches!(self, Binding::Gamepad(_))
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone)]
+pub struct Controls {
+    bindings: BTreeMap<Action, Vec<Binding>>,
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        let bindings = BTreeMap::from([
+            (
+                Action::RaiseSails,
+                vec![
+                    Binding::Key(KeyCode::S),
+                    Binding::Gamepad(GamepadButtonType::DPadDown),
+                ],
+            ),
+            (
+                Action::LowerSails,
+                vec![
+                    Binding::Key(KeyCode::W),
+                    Binding::Gamepad(GamepadButtonType::DPadUp),
+                ],
+            ),
+            (
+                Action::TurnPort,
+                vec![
+                    Binding::Key(KeyCode::A),
+                    Binding::Gamepad(GamepadButtonType::DPadLeft),
+                ],
+            ),
+            (
+                Action::TurnStarboard,
+                vec![
+                    Binding::Key(KeyCode::D),
+                    Binding::Gamepad(GamepadButtonType::DPadRight),
+                ],
+            ),
+            (
+                Action::FirePort,
+                vec![
+                    Binding::Key(KeyCode::Q),
+                    Binding::Gamepad(GamepadButtonType::LeftTrigger2),
+                ],
+            ),
+            (
+                Action::FireStarboard,
+                vec![
+                    Binding::Key(KeyCode::E),
+                    Binding::Gamepad(GamepadButtonType::RightTrigger2),
+                ],
+            ),
+            (
+                Action::AimPort,
+                vec![
+                    Binding::Key(KeyCode::Z),
+                    Binding::Gamepad(GamepadButtonType::LeftTrigger),
+                ],
+            ),
+            (
+                Action::AimStarboard,
+                vec![
+                    Binding::Key(KeyCode::C),
+                    Binding::Gamepad(GamepadButtonType::RightTrigger),
+                ],
+            ),
+            (
+                Action::ElevateCannons,
+                vec![
+                    Binding::Key(KeyCode::R),
+                    Binding::Gamepad(GamepadButtonType::North),
+                ],
+            ),
+            (
+                Action::DepressCannons,
+                vec![
+                    Binding::Key(KeyCode::F),
+                    Binding::Gamepad(GamepadButtonType::West),
+                ],
+            ),
+            (
+                Action::NextAmmo,
+                vec![
+                    Binding::Key(KeyCode::X),
+                    Binding::Gamepad(GamepadButtonType::RightThumb),
+                ],
+            ),
+            (
+                Action::Repair,
+                vec![
+                    Binding::Key(KeyCode::G),
+                    Binding::Gamepad(GamepadButtonType::Select),
+                ],
+            ),
+            (
+                Action::Board,
+                vec![
+                    Binding::Key(KeyCode::B),
+                    Binding::Gamepad(GamepadButtonType::East),
+                ],
+            ),
+            (
+                Action::SwitchShip,
+                vec![
+                    Binding::Key(KeyCode::Tab),
+                    Binding::Gamepad(GamepadButtonType::LeftThumb),
+                ],
+            ),
+            (Action::FormUp, vec![Binding::Key(KeyCode::Key1)]),
+            (Action::Attack, vec![Binding::Key(KeyCode::Key2)]),
+            (Action::Hold, vec![Binding::Key(KeyCode::Key3)]),
+            (Action::Retreat, vec![Binding::Key(KeyCode::Key4)]),
+            (
+                Action::FleetMenu,
+                vec![
+                    Binding::Key(KeyCode::V),
+                    Binding::Gamepad(GamepadButtonType::South),
+                ],
+            ),
+            (Action::Spectate, vec![Binding::Key(KeyCode::O)]),
+            (
+                Action::Pause,
+                vec![
+                    Binding::Key(KeyCode::Escape),
+                    Binding::Gamepad(GamepadButtonType::Start),
+                ],
+            ),
+        ]);
+        Self { bindings }
+    }
+}
+
+impl Controls {
+    /// Reads the controls config, falling back to the default bindings if there isn't a usable one.
+    /// Actions added since the config was written get their default bindings.
+    pub fn load() -> Self {
+        if !Path::new(CONTROLS_FILE).exists() {
+            return Self::default();
+        }
+        match fs::read_to_string(CONTROLS_FILE)
+            .map_err(|error| error.to_string())
+            .and_then(|contents| {
+                ron::from_str::<Self>(&contents).map_err(|error| error.to_string())
+            }) {
+            Ok(mut controls) => {
+                for (action, bindings) in Self::default().bindings {
+                    controls.bindings.entry(action).or_insert(bindings);
+                }
+                controls
+            }
+            Err(error) => {
+                println!("Failed to load controls, using the defaults: {}", error);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let written = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|error| error.to_string())
+            .and_then(|contents| {
+                fs::create_dir_all(CONTROLS_DIRECTORY)
+                    .and_then(|_| fs::write(CONTROLS_FILE, contents))
+                    .map_err(|error| error.to_string())
+            });
+        if let Err(error) = written {
+            println!("Failed to save controls: {}", error);
+        }
+    }
+
+    pub fn bindings(&self, action: Action) -> &[Binding] {
+        self.bindings
+            .get(&action)
+            .map(|bindings| bindings.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Replaces the action's binding on the same device, taking the binding away from any action that already had it.
+    /// Pause is never left without a way to reach it, so it takes the action's old binding in exchange, and the
+    /// rebind is refused if there isn't one. Returns whether the binding was changed.
+    pub fn rebind(&mut self, action: Action, binding: Binding) -> bool {
+        if action != Action::Pause && self.bindings(Action::Pause).contains(&binding) {
+            let Some(previous) = self
+                .bindings(action)
+                .iter()
+                .find(|existing| existing.is_gamepad() == binding.is_gamepad())
+                .copied()
+            else {
+                return false;
+            };
+            self.bindings
+                .entry(Action::Pause)
+                .or_default()
+                .push(previous);
+        }
+        for bindings in self.bindings.values_mut() {
+            bindings.retain(|existing| *existing != binding);
+        }
+        let bindings = self.bindings.entry(action).or_default();
+        bindings.retain(|existing| existing.is_gamepad() != binding.is_gamepad());
+        bindings.push(binding);
+        true
+    }
+}
+
+/// Resolves actions against the current keyboard, mouse and gamepad state.
+#[derive(SystemParam)]
+pub struct Actions<'w> {
+    controls: Res<'w, Controls>,
+    keyboard: Res<'w, Input<KeyCode>>,
+    mouse: Res<'w, Input<MouseButton>>,
+    gamepad_buttons: Res<'w, Input<GamepadButton>>,
+    gamepads: Res<'w, Gamepads>,
+    gamepad_axes: Res<'w, Axis<GamepadAxis>>,
+}
+
+impl<'w> Actions<'w> {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.controls
+            .bindings(action)
+            .iter()
+            .any(|binding| match *binding {
+                Binding::Key(key) => self.keyboard.pressed(key),
+                Binding::Mouse(button) => self.mouse.pressed(button),
+                Binding::Gamepad(button) => self.gamepads.iter().any(|gamepad| {
+                    self.gamepad_buttons
+                        .pressed(GamepadButton::new(gamepad, button))
+                }),
+            })
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.controls
+            .bindings(action)
+            .iter()
+            .any(|binding| match *binding {
+                Binding::Key(key) => self.keyboard.just_pressed(key),
+                Binding::Mouse(button) => self.mouse.just_pressed(button),
+                Binding::Gamepad(button) => self.gamepads.iter().any(|gamepad| {
+                    self.gamepad_buttons
+                        .just_pressed(GamepadButton::new(gamepad, button))
+                }),
+            })
+    }
+
+    /// How hard to put the helm over, from -1 (starboard) to 1 (port).
+    /// Turn actions put it hard over, the left stick anywhere in between.
+    pub fn helm(&self) -> f32 {
+        let mut helm = 0.0;
+        if self.pressed(Action::TurnPort) {
+            helm += 1.0;
+        }
+        if self.pressed(Action::TurnStarboard) {
+            helm -= 1.0;
+        }
+        // Pushing the stick right turns to starboard
+        helm -= self
+            .stick(GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY)
+            .x;
+        helm.clamp(-1.0, 1.0)
+    }
+
+    /// Which way to fly the spectator camera, relative to where it's looking: x to the right, y up and z forwards.
+    /// The sail and turn actions, or the left stick, fly it around, and the elevation actions raise and lower it.
+    pub fn fly(&self) -> Vec3 {
+        let mut fly = Vec3::ZERO;
+        if self.pressed(Action::LowerSails) {
+            fly.z += 1.0;
+        }
+        if self.pressed(Action::RaiseSails) {
+            fly.z -= 1.0;
+        }
+        if self.pressed(Action::TurnStarboard) {
+            fly.x += 1.0;
+        }
+        if self.pressed(Action::TurnPort) {
+            fly.x -= 1.0;
+        }
+        if self.pressed(Action::ElevateCannons) {
+            fly.y += 1.0;
+        }
+        if self.pressed(Action::DepressCannons) {
+            fly.y -= 1.0;
+        }
+        let stick = self.stick(GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY);
+        (fly + Vec3::new(stick.x, 0.0, stick.y)).clamp_length_max(1.0)
+    }
+
+    /// Right stick position, for looking around.
+    pub fn look(&self) -> Vec2 {
+        self.stick(GamepadAxisType::RightStickX, GamepadAxisType::RightStickY)
+    }
+
+    /// Position of a stick on the first gamepad pushing it past the deadzone.
+    fn stick(&self, x: GamepadAxisType, y: GamepadAxisType) -> Vec2 {
+        self.gamepads
+            .iter()
+            .map(|gamepad| {
+                Vec2::new(
+                    self.gamepad_axes
+                        .get(GamepadAxis::new(gamepad, x))
+                        .unwrap_or_default(),
+                    self.gamepad_axes
+                        .get(GamepadAxis::new(gamepad, y))
+                        .unwrap_or_default(),
+                )
+            })
+            .find(|stick| stick.length() > STICK_DEADZONE)
+            .unwrap_or(Vec2::ZERO)
+    }
+}
+
+pub struct Systems;
+impl Systems {
+    pub fn load_controls(mut commands: Commands) {
+        commands.insert_resource(Controls::load());
+    }
+}
@@ -0,0 +1,198 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::Path};
+
+const FACTIONS_FILE: &str = "assets/factions.ron";
+// Each faction takes two of Rapier's 32 collision groups, one for its ships and one for its cannon balls
+pub const MAX_FACTIONS: usize = 16;
+
+/// The side a ship sails for, see `Factions` for how the sides get along.
+#[derive(
+    Component,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Debug,
+    Default,
+)]
+pub struct Faction(u8);
+
+impl Faction {
+    // The first faction in the table
+    pub const PLAYER: Faction = Faction(0);
+
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Relation {
+    // Won't fire on each other, and cannon balls pass through each other's ships
+    Allied,
+    // Leave each other be, but can still be hit by a stray broadside
+    Neutral,
+    // Attack each other on sight
+    Hostile,
+}
+
+/// Every faction at sea and how each pair of them get along.
+#[derive(Resource)]
+pub struct Factions {
+    names: Vec<String>,
+    // Keyed by each pair of factions, lowest first. Pairs that aren't listed are neutral.
+    relations: HashMap<(Faction, Faction), Relation>,
+}
+
+/// The faction table as written in `assets/factions.ron`, with factions referred to by name.
+#[derive(Deserialize)]
+struct FactionTable {
+    factions: Vec<String>,
+    relations: Vec<(String, String, Relation)>,
+}
+
+impl Default for FactionTable {
+    fn default() -> Self {
+        let hostile = |a: &str, b: &str| (a.to_string(), b.to_string(), Relation::Hostile);
+        Self {
+            factions: ["Player", "Navy", "Pirates", "Merchants"]
+                .map(String::from)
+                .to_vec(),
+            relations: vec![
+                hostile("Player", "Navy"),
+                hostile("Player", "Pirates"),
+                hostile("Navy", "Pirates"),
+                hostile("Merchants", "Pirates"),
+            ],
+        }
+    }
+}
+
+impl Default for Factions {
+    fn default() -> Self {
+        Self::from_table(FactionTable::default())
+    }
+}
+
+impl Factions {
+    /// Reads the faction table, falling back to the default one if there isn't a usable one.
+    pub fn load() -> Self {
+        if !Path::new(FACTIONS_FILE).exists() {
+            return Self::default();
+        }
+        match fs::read_to_string(FACTIONS_FILE)
+            .map_err(|error| error.to_string())
+            .and_then(|contents| {
+                ron::from_str::<FactionTable>(&contents).map_err(|error| error.to_string())
+            }) {
+            Ok(table) if !table.factions.is_empty() => Self::from_table(table),
+            Ok(_) => {
+                println!("The faction table is empty, using the defaults");
+                Self::default()
+            }
+            Err(error) => {
+                println!("Failed to load factions, using the defaults: {}", error);
+                Self::default()
+            }
+        }
+    }
+
+    fn from_table(table: FactionTable) -> Self {
+        let mut factions = Self {
+            names: Vec::new(),
+            relations: HashMap::new(),
+        };
+        for name in &table.factions {
+            factions.add(name);
+        }
+        for (a, b, relation) in &table.relations {
+            match (factions.find(a), factions.find(b)) {
+                (Some(a), Some(b)) => factions.set_relation(a, b, *relation),
+                _ => println!(
+                    "Ignoring the relation between {} and {}, they aren't both in the table",
+                    a, b
+                ),
+            }
+        }
+        factions
+    }
+
+    /// Registers a new faction, neutral towards all the others.
+    /// Returns `None` once every faction's collision groups have been handed out.
+    pub fn add(&mut self, name: &str) -> Option<Faction> {
+        if self.names.len() >= MAX_FACTIONS {
+            println!(
+                "Can't add the {} faction, there's no room left at sea",
+                name
+            );
+            return None;
+        }
+        self.names.push(name.to_string());
+        Some(Faction((self.names.len() - 1) as u8))
+    }
+
+    pub fn find(&self, name: &str) -> Option<Faction> {
+        self.names
+            .iter()
+            .position(|existing| existing == name)
+            .map(|index| Faction(index as u8))
+    }
+
+    /// Looks up a faction by name, registering it if it's new, like when a spawner sends out ships under a new flag.
+    pub fn find_or_add(&mut self, name: &str) -> Option<Faction> {
+        self.find(name).or_else(|| self.add(name))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Faction> {
+        (0..self.names.len() as u8).map(Faction)
+    }
+
+    pub fn name(&self, faction: Faction) -> &str {
+        self.names
+            .get(faction.index())
+            .map(|name| name.as_str())
+            .unwrap_or("Unknown")
+    }
+
+    /// Every faction is allied with itself.
+    pub fn relation(&self, a: Faction, b: Faction) -> Relation {
+        if a == b {
+            return Relation::Allied;
+        }
+        self.relations
+            .get(&Self::key(a, b))
+            .copied()
+            .unwrap_or(Relation::Neutral)
+    }
+
+    pub fn set_relation(&mut self, a: Faction, b: Faction, relation: Relation) {
+        if a != b {
+            self.relations.insert(Self::key(a, b), relation);
+        }
+    }
+
+    pub fn is_hostile(&self, a: Faction, b: Faction) -> bool {
+        self.relation(a, b) == Relation::Hostile
+    }
+
+    pub fn is_allied(&self, a: Faction, b: Faction) -> bool {
+        self.relation(a, b) == Relation::Allied
+    }
+
+    fn key(a: Faction, b: Faction) -> (Faction, Faction) {
+        (a.min(b), a.max(b))
+    }
+}
+
+pub struct Systems;
+impl Systems {
+    pub fn load_factions(mut commands: Commands) {
+        commands.insert_resource(Factions::load());
+    }
+}
@@ -10,16 +10,26 @@ pub struct Health {
 
 impl Default for Health {
     fn default() -> Self {
+        Self::new(100.0)
+    }
+}
+
+impl Health {
+    pub fn new(max_health: f32) -> Self {
         Self {
-            max_health: 100.0,
-            starting_health: 100.0,
-            current_health: 100.0,
+            max_health,
+            starting_health: max_health,
+            current_health: max_health,
             dead: false,
         }
     }
-}
 
-impl Health {
+    /// Changes max health, keeping the same proportion of health as before.
+    pub fn set_max(&mut self, max_health: f32) {
+        self.current_health = self.fraction() * max_health;
+        self.max_health = max_health;
+    }
+
     pub fn take_damage(&mut self, amount: f32) {
         if self.dead {
             return;
@@ -28,13 +38,35 @@ impl Health {
         if self.current_health - amount <= 0.0 {
             self.dead = true;
             self.current_health = 0.0;
-            println!("I'm dead!");
             return;
         }
 
         self.current_health -= amount;
     }
 
+    pub fn current(&self) -> f32 {
+        self.current_health
+    }
+
+    pub fn max(&self) -> f32 {
+        self.max_health
+    }
+
+    /// Sets current health directly, such as when restoring a saved game.
+    pub fn set_current(&mut self, current_health: f32) {
+        self.current_health = current_health.clamp(0.0, self.max_health);
+        self.dead = self.current_health <= 0.0;
+    }
+
+    /// Remaining health, from 0.0 (dead) to 1.0 (full health)
+    pub fn fraction(&self) -> f32 {
+        self.current_health / self.max_health
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.dead
+    }
+
     pub fn heal(&mut self, amount: f32) {
         if self.dead {
             return;
@@ -1,16 +1,20 @@
 use bevy::prelude::*;
 
 #[derive(Component)]
-pub struct Name(&'static str);
+pub struct Name(String);
 
 impl Default for Name {
     fn default() -> Self {
-        Self("Unnamed")
+        Self("Unnamed".to_string())
     }
 }
 
 impl Name {
-    pub fn new(name: &'static str) -> Self {
-        Self(name)
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    pub fn get(&self) -> &str {
+        &self.0
     }
 }
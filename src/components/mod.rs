@@ -1,4 +1,8 @@
 pub mod collider_group;
+pub mod controls;
+pub mod faction;
 pub mod health;
 pub mod id;
+pub mod session;
 pub mod ship;
+pub mod wind;
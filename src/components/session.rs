@@ -0,0 +1,91 @@
+use super::faction::{Faction, Factions};
+use super::id::Name;
+use super::ship::{
+    boarding::ShipCaptured,
+    class::ShipClass,
+    save::LoadedSave,
+    sinking::{ShipDestroyed, Sinking},
+    PlayerShip, Ship, ShipAssets, ShipMarker,
+};
+use crate::MenuState;
+use bevy::{asset::LoadState, prelude::*};
+
+/// Marks entities that belong to a single voyage, so they can be cleared away before the next one.
+#[derive(Component, Default, Clone)]
+pub struct SessionEntity;
+
+pub struct Systems;
+impl Systems {
+    pub fn load_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
+        commands.insert_resource(ShipAssets::load(&asset_server));
+    }
+
+    /// Sets sail once the ship classes and their models have loaded. Already loaded assets pass straight through, so restarting is instant.
+    pub fn wait_for_assets(
+        asset_server: Res<AssetServer>,
+        ship_assets: Res<ShipAssets>,
+        classes: Res<Assets<ShipClass>>,
+        save: Option<Res<LoadedSave>>,
+        mut next_game_state: ResMut<NextState<MenuState>>,
+    ) {
+        // The model can only be waited on once the class file has told us which one it is
+        let load_state = match (save, classes.get(&ship_assets.dutch_ship)) {
+            (Some(save), _) => save.load_state(&asset_server, &classes),
+            (None, Some(class)) => asset_server.get_load_state(&class.scene),
+            (None, None) => asset_server.get_load_state(&ship_assets.dutch_ship),
+        };
+        match load_state {
+            LoadState::Loaded => next_game_state.set(MenuState::Ship),
+            LoadState::Failed => {
+                println!("Failed to load the ship class, heading back to port ⚓");
+                next_game_state.set(MenuState::MainMenu);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn clean_up(mut commands: Commands, entities: Query<Entity, With<SessionEntity>>) {
+        for entity in entities.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    /// Ends the voyage when the player's ship goes down or loses her whole crew, or when the last ship hostile to the
+    /// player is sunk or captured.
+    pub fn end_session(
+        mut destroyed: EventReader<ShipDestroyed>,
+        mut captured: EventReader<ShipCaptured>,
+        ships: Query<(Entity, &Faction), Without<Sinking>>,
+        player: Query<(&Ship, &Name), With<PlayerShip>>,
+        factions: Res<Factions>,
+        mut next_game_state: ResMut<NextState<MenuState>>,
+    ) {
+        // A ship with no one left aboard can neither sail nor fight
+        if let Ok((ship, name)) = player.get_single() {
+            if ship.crew().is_dead() {
+                println!("With no crew left to sail her, {} is lost", name.get());
+                next_game_state.set(MenuState::GameOver);
+                return;
+            }
+        }
+
+        let mut sunk: Vec<Entity> = Vec::new();
+        for event in destroyed.iter() {
+            if event.marker == ShipMarker::Player {
+                next_game_state.set(MenuState::GameOver);
+                return;
+            }
+            sunk.push(event.entity);
+        }
+        sunk.extend(captured.iter().map(|event| event.entity));
+
+        // Ships destroyed this frame won't have started sinking yet
+        let mut enemies = ships
+            .iter()
+            .filter(|(_, faction)| factions.is_hostile(Faction::PLAYER, **faction))
+            .map(|(entity, _)| entity);
+        if !sunk.is_empty() && enemies.all(|enemy| sunk.contains(&enemy)) {
+            next_game_state.set(MenuState::Victory);
+        }
+    }
+}
@@ -0,0 +1,333 @@
+use super::cannons::{self, CannonDirection, Cannons};
+use super::sails::SailState;
+use super::sections::Section;
+use super::sinking::Sinking;
+use super::Ship;
+use crate::components::{
+    collider_group,
+    faction::{Faction, Factions},
+    wind::Wind,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{RapierConfiguration, Velocity};
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_8};
+
+// How close a hostile ship needs to be before a patrolling ship gives chase
+const DETECTION_RANGE: f32 = 250.0;
+// How close a ship needs to be before it turns to bring its cannons to bear
+const BROADSIDE_RANGE: f32 = 120.0;
+// Ships run for it once their health drops below this fraction
+const FLEE_HEALTH: f32 = 0.25;
+const PATROL_RADIUS: f32 = 60.0;
+// How close a ship needs to get to a patrol waypoint before moving on to the next one
+const WAYPOINT_RADIUS: f32 = 15.0;
+// How far off a perfect broadside the ship can be and still fire, in radians
+const BROADSIDE_TOLERANCE: f32 = 0.15;
+// Heading error, in radians, at which the ship puts the helm hard over
+const HARD_OVER_ANGLE: f32 = FRAC_PI_8;
+// How far from its station a ship can drift before it makes sail to close up
+const STATION_RADIUS: f32 = 10.0;
+// Beyond this far from its station, a ship stops keeping pace and sails straight for it
+const STATION_CATCH_UP: f32 = 40.0;
+// How far off the beam a target can be for a ship that isn't steering for a broadside to fire on it, in radians
+const BEARING_ARC: f32 = 0.3;
+
+type AiShip<'a> = (
+    Entity,
+    &'a mut Transform,
+    &'a mut Velocity,
+    &'a mut Ship,
+    &'a mut ShipAi,
+    &'a Faction,
+);
+// Read from the global transform, as the ships doing the targeting have their own transforms borrowed
+type AiTarget<'a> = (Entity, &'a GlobalTransform, &'a Faction);
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AiState {
+    Patrol,
+    Chase,
+    Broadside,
+    Fire(CannonDirection),
+    Flee,
+    Station,
+    Hold,
+}
+
+/// What the player has ordered an allied ship to do, see `fleet`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Orders {
+    // Keep to a point relative to the leader, matching their heading and sails
+    Station {
+        position: Vec3,
+        heading: Vec3,
+        sails: SailState,
+    },
+    Attack(Entity),
+    Hold,
+    Retreat,
+}
+
+#[derive(Component)]
+pub struct ShipAi {
+    state: AiState,
+    home: Vec3,
+    waypoint: Vec3,
+    // Left to its own devices without any
+    orders: Option<Orders>,
+}
+
+impl ShipAi {
+    pub fn new(home: Vec3) -> Self {
+        Self {
+            state: AiState::Patrol,
+            home,
+            waypoint: home + Vec3::new(PATROL_RADIUS, 0.0, 0.0),
+            orders: None,
+        }
+    }
+
+    pub fn set_orders(&mut self, orders: Option<Orders>) {
+        self.orders = orders;
+    }
+
+    /// Picks the state the ship should be in, given where its target is relative to it.
+    fn next_state(ship: &Ship, transform: &Transform, to_target: Option<Vec3>) -> AiState {
+        let Some(to_target) = to_target else {
+            return AiState::Patrol;
+        };
+        let distance = to_target.length();
+
+        if distance >= DETECTION_RANGE {
+            return AiState::Patrol;
+        }
+        if ship.health.fraction() < FLEE_HEALTH {
+            return AiState::Flee;
+        }
+        if distance >= BROADSIDE_RANGE {
+            return AiState::Chase;
+        }
+
+        let (side, heading) = broadside_heading(ship, transform, to_target.normalize_or_zero());
+        if heading_error(transform, heading).abs() < BROADSIDE_TOLERANCE {
+            AiState::Fire(side)
+        } else {
+            AiState::Broadside
+        }
+    }
+
+    /// Moves the patrol waypoint a quarter turn around the ship's home once it has been reached.
+    fn patrol_heading(&mut self, transform: &Transform) -> Vec3 {
+        let to_waypoint = flatten(self.waypoint - transform.translation);
+        if to_waypoint.length() < WAYPOINT_RADIUS {
+            self.waypoint =
+                self.home + Quat::from_rotation_y(FRAC_PI_2) * (self.waypoint - self.home);
+        }
+        flatten(self.waypoint - transform.translation).normalize_or_zero()
+    }
+}
+
+/// Sails and heading that keep a ship on its station, closing up from wherever it has fallen to.
+fn station_course(
+    transform: &Transform,
+    position: Vec3,
+    heading: Vec3,
+    sails: &SailState,
+) -> (SailState, Vec3) {
+    let to_station = flatten(position - transform.translation);
+    if to_station.length() > STATION_CATCH_UP {
+        return (SailState::Full, to_station.normalize_or_zero());
+    }
+    // How far the ship has dropped astern of its station, negative once it has overshot
+    let astern = to_station.dot(heading);
+    let sails = if astern > STATION_RADIUS {
+        sails.speed_up()
+    } else if astern < -STATION_RADIUS {
+        sails.slow_down()
+    } else {
+        sails.clone()
+    };
+    // Edge back towards the station while following the leader's heading
+    let heading = (heading + to_station / STATION_CATCH_UP).normalize_or_zero();
+    (sails, heading)
+}
+
+/// The side a target already lies abeam of, if it's in range, for ships that aren't free to turn towards it.
+fn bearing_side(transform: &Transform, to_target: Vec3) -> Option<CannonDirection> {
+    if to_target.length() >= BROADSIDE_RANGE {
+        return None;
+    }
+    let right = flatten(transform.local_x()).normalize_or_zero();
+    let to_target = to_target.normalize_or_zero();
+    [
+        (CannonDirection::Right, right),
+        (CannonDirection::Left, -right),
+    ]
+    .into_iter()
+    .find(|(_, side)| side.angle_between(to_target) < BEARING_ARC)
+    .map(|(side, _)| side)
+}
+
+/// Projects a vector onto the water plane.
+fn flatten(vector: Vec3) -> Vec3 {
+    Vec3::new(vector.x, 0.0, vector.z)
+}
+
+/// Signed angle from the ship's heading to `direction`, positive when `direction` is to the left.
+fn heading_error(transform: &Transform, direction: Vec3) -> f32 {
+    let forward = flatten(transform.forward()).normalize_or_zero();
+    let cross = forward.cross(direction).y;
+    cross.atan2(forward.dot(direction))
+}
+
+/// Picks the side to present to the target, whichever needs the least turning, and the heading to get there.
+/// A side whose battery has been knocked out is only picked if the other one has been too.
+fn broadside_heading(
+    ship: &Ship,
+    transform: &Transform,
+    to_target: Vec3,
+) -> (CannonDirection, Vec3) {
+    // Right cannons fire along the ship's local x axis, so the target sits a quarter turn to the right of the heading
+    let right = Quat::from_rotation_y(FRAC_PI_2) * to_target;
+    let left = Quat::from_rotation_y(-FRAC_PI_2) * to_target;
+    let left_out = ship
+        .section(Section::Battery(CannonDirection::Left))
+        .is_dead();
+    let right_out = ship
+        .section(Section::Battery(CannonDirection::Right))
+        .is_dead();
+    let use_right = match (left_out, right_out) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => heading_error(transform, right).abs() <= heading_error(transform, left).abs(),
+    };
+    if use_right {
+        (CannonDirection::Right, right)
+    } else {
+        (CannonDirection::Left, left)
+    }
+}
+
+pub struct Systems;
+impl Systems {
+    /// Drives AI ships with the same sails, helm and cannons available to the player.
+    /// Each one goes after the nearest ship of a faction hostile to its own, unless it has orders otherwise.
+    pub fn behaviour(
+        mut commands: Commands,
+        mut ships: Query<AiShip, Without<Sinking>>,
+        targets: Query<AiTarget, Without<Sinking>>,
+        factions: Res<Factions>,
+        wind: Res<Wind>,
+        rapier: Res<RapierConfiguration>,
+        time: Res<Time>,
+    ) {
+        let gravity = cannons::cannon_gravity(&rapier);
+        let targets: Vec<(Entity, Vec3, Faction)> = targets
+            .iter()
+            .map(|(entity, transform, faction)| (entity, transform.translation(), *faction))
+            .collect();
+
+        for (entity, mut transform, mut velocity, mut ship, mut ai, faction) in ships.iter_mut() {
+            let ordered_target = match ai.orders {
+                // A target that's since been captured by a friendly faction is left alone
+                Some(Orders::Attack(target)) => targets
+                    .iter()
+                    .find(|(entity, _, target_faction)| {
+                        *entity == target && factions.is_hostile(*faction, *target_faction)
+                    })
+                    .map(|(_, target, _)| flatten(*target - transform.translation)),
+                _ => None,
+            };
+            let to_target = ordered_target.or_else(|| {
+                targets
+                    .iter()
+                    .filter(|(target, _, target_faction)| {
+                        *target != entity && factions.is_hostile(*faction, *target_faction)
+                    })
+                    .map(|(_, target, _)| flatten(*target - transform.translation))
+                    .min_by(|a, b| a.length().total_cmp(&b.length()))
+            });
+            ai.state = match ai.orders {
+                Some(Orders::Station { .. }) => AiState::Station,
+                Some(Orders::Hold) => AiState::Hold,
+                Some(Orders::Retreat) => AiState::Flee,
+                // Ordered attacks are pressed home from any range
+                _ => match ShipAi::next_state(&ship, &transform, to_target) {
+                    AiState::Patrol if ordered_target.is_some() => AiState::Chase,
+                    state => state,
+                },
+            };
+
+            let mut firing = None;
+            let (sails, heading) = match (ai.state, to_target) {
+                (AiState::Chase, Some(to_target)) => {
+                    (SailState::Full, to_target.normalize_or_zero())
+                }
+                (AiState::Broadside, Some(to_target)) => (
+                    SailState::Mid,
+                    broadside_heading(&ship, &transform, to_target.normalize_or_zero()).1,
+                ),
+                (AiState::Fire(side), Some(to_target)) => {
+                    firing = Some(side);
+                    (
+                        SailState::Mid,
+                        broadside_heading(&ship, &transform, to_target.normalize_or_zero()).1,
+                    )
+                }
+                (AiState::Flee, Some(to_target)) => {
+                    (SailState::Full, -to_target.normalize_or_zero())
+                }
+                (AiState::Station, to_target) => {
+                    firing = to_target.and_then(|to_target| bearing_side(&transform, to_target));
+                    match &ai.orders {
+                        Some(Orders::Station {
+                            position,
+                            heading,
+                            sails,
+                        }) => station_course(&transform, *position, *heading, sails),
+                        _ => (SailState::None, flatten(transform.forward())),
+                    }
+                }
+                (AiState::Hold, to_target) => {
+                    firing = to_target.and_then(|to_target| bearing_side(&transform, to_target));
+                    (SailState::None, flatten(transform.forward()))
+                }
+                _ => (SailState::Mid, ai.patrol_heading(&transform)),
+            };
+
+            if let (Some(side), Some(to_target)) = (firing, to_target) {
+                // Switch to whatever is left in the magazine once the current ammo runs out
+                if ship.ammo.remaining(ship.ammo.selected()) == 0 {
+                    ship.ammo.select_next();
+                }
+                let has_ammo = ship.ammo.remaining(ship.ammo.selected()) > 0;
+                let battery_working = !ship.section(Section::Battery(side)).is_dead();
+                let manned = !ship.crew.is_dead();
+                if has_ammo
+                    && battery_working
+                    && manned
+                    && ship.cannons.is_loaded(time.elapsed(), side)
+                {
+                    // Lay the guns for the target's range before letting fly
+                    ship.cannons.set_elevation(
+                        side,
+                        Cannons::elevation_for_range(to_target.length(), gravity),
+                    );
+                    ship.fire(
+                        &mut commands,
+                        time.elapsed(),
+                        &transform,
+                        &velocity,
+                        side,
+                        collider_group::cannon_collision_groups(*faction, &factions),
+                    );
+                }
+            }
+
+            ship.set_sails(sails);
+            let helm = heading_error(&transform, heading) / HARD_OVER_ANGLE;
+            ship.turn(&mut transform, helm, &wind, time.delta_seconds());
+            *velocity = ship.velocity(&transform, &wind);
+        }
+    }
+}
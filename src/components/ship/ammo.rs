@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub enum AmmoType {
+    // Smashes whatever it hits
+    #[default]
+    Round,
+    // Tears up the masts and rigging wherever it hits, leaving the ship unable to carry as much sail
+    Chain,
+    // Cuts down the crew, slowing their reloads
+    Grape,
+    // Hits harder, but takes longer to reload
+    Heavy,
+}
+
+/// What a single ball does to the ship it hits.
+pub struct ShotDamage {
+    // Damage to the section the ball hits
+    pub section: f32,
+    pub masts: f32,
+    pub crew: f32,
+}
+
+impl AmmoType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Round => "Round shot",
+            Self::Chain => "Chain shot",
+            Self::Grape => "Grape shot",
+            Self::Heavy => "Heavy shot",
+        }
+    }
+
+    pub fn damage(&self) -> ShotDamage {
+        match self {
+            Self::Round => ShotDamage {
+                section: 10.0,
+                masts: 0.0,
+                crew: 0.0,
+            },
+            Self::Chain => ShotDamage {
+                section: 2.0,
+                masts: 12.0,
+                crew: 0.0,
+            },
+            Self::Grape => ShotDamage {
+                section: 1.0,
+                masts: 0.0,
+                crew: 8.0,
+            },
+            Self::Heavy => ShotDamage {
+                section: 20.0,
+                masts: 0.0,
+                crew: 0.0,
+            },
+        }
+    }
+
+    /// How much longer than usual the cannons take to reload after firing this ammo.
+    pub fn reload_multiplier(&self) -> f32 {
+        match self {
+            Self::Heavy => 1.8,
+            _ => 1.0,
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            Self::Round => Self::Chain,
+            Self::Chain => Self::Grape,
+            Self::Grape => Self::Heavy,
+            Self::Heavy => Self::Round,
+        }
+    }
+}
+
+/// Cannon balls a ship class sets sail with, see `ShipClass`.
+#[derive(Deserialize, Clone)]
+pub struct AmmoConfig {
+    pub round: u32,
+    pub chain: u32,
+    pub grape: u32,
+    pub heavy: u32,
+}
+
+impl Default for AmmoConfig {
+    fn default() -> Self {
+        Self {
+            round: 300,
+            chain: 45,
+            grape: 45,
+            heavy: 30,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct AmmoStores {
+    selected: AmmoType,
+    round: u32,
+    chain: u32,
+    grape: u32,
+    heavy: u32,
+}
+
+impl AmmoStores {
+    pub fn new(config: &AmmoConfig) -> Self {
+        Self {
+            selected: AmmoType::Round,
+            round: config.round,
+            chain: config.chain,
+            grape: config.grape,
+            heavy: config.heavy,
+        }
+    }
+
+    pub fn selected(&self) -> AmmoType {
+        self.selected
+    }
+
+    /// Cycles to the next kind of ammo, skipping any that have run out.
+    pub fn select_next(&mut self) {
+        let mut ammo = self.selected.next();
+        while ammo != self.selected && self.remaining(ammo) == 0 {
+            ammo = ammo.next();
+        }
+        self.selected = ammo;
+    }
+
+    pub fn remaining(&self, ammo: AmmoType) -> u32 {
+        match ammo {
+            AmmoType::Round => self.round,
+            AmmoType::Chain => self.chain,
+            AmmoType::Grape => self.grape,
+            AmmoType::Heavy => self.heavy,
+        }
+    }
+
+    /// Takes up to `count` balls of the selected ammo from the stores, returning how many there were.
+    pub fn take(&mut self, count: u32) -> u32 {
+        let stores = match self.selected {
+            AmmoType::Round => &mut self.round,
+            AmmoType::Chain => &mut self.chain,
+            AmmoType::Grape => &mut self.grape,
+            AmmoType::Heavy => &mut self.heavy,
+        };
+        let taken = count.min(*stores);
+        *stores -= taken;
+        taken
+    }
+}
@@ -0,0 +1,187 @@
+use super::sinking::Sinking;
+use super::{ai::ShipAi, PlayerShip, Ship, ShipMarker};
+use crate::components::{
+    collider_group,
+    controls::{Action, Actions},
+    faction::{Faction, Factions},
+    id::Name,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+
+// How close the ships' centres need to be for the player to come alongside
+const BOARDING_RANGE: f32 = 30.0;
+// Fastest the two ships can be moving relative to each other for the grapples to hold
+const BOARDING_SPEED: f32 = 6.0;
+// Hull fraction below which an enemy can be boarded
+const BOARDABLE_HULL: f32 = 0.35;
+// How far either side's strength can stray from its crew count, so the outcome is never a sure thing
+const BOARDING_LUCK: f32 = 0.25;
+// Share of the other side's strength each side loses in the fighting
+const BOARDING_CASUALTIES: f32 = 0.5;
+// Share of the boarders' surviving crew that stays aboard a captured ship to sail her
+const PRIZE_CREW_SHARE: f32 = 0.3;
+
+type Boarder = (With<PlayerShip>, Without<Sinking>);
+type Boardable = (Without<PlayerShip>, Without<Sinking>);
+type Prize<'a> = (
+    &'a mut Ship,
+    &'a mut Faction,
+    &'a mut ShipAi,
+    &'a Transform,
+    &'a Name,
+);
+type Command<'a> = (
+    Entity,
+    &'a Transform,
+    &'a mut ShipMarker,
+    &'a Faction,
+    &'a Name,
+);
+
+#[derive(Event)]
+pub struct ShipCaptured {
+    pub entity: Entity,
+}
+
+/// The enemy ship the player is in a position to board, if any.
+#[derive(Resource, Default)]
+pub struct BoardingTarget(pub Option<Entity>);
+
+/// A side's strength in a boarding action.
+fn boarding_strength(ship: &Ship) -> f32 {
+    ship.crew.current() * (1.0 + BOARDING_LUCK * (fastrand::f32() * 2.0 - 1.0))
+}
+
+pub struct Systems;
+impl Systems {
+    /// Finds the nearest crippled hostile ship the player has come alongside at low speed.
+    pub fn find_target(
+        mut target: ResMut<BoardingTarget>,
+        player: Query<(&Transform, &Velocity, &Faction), Boarder>,
+        ships: Query<(Entity, &Ship, &Transform, &Velocity, &Faction), Boardable>,
+        factions: Res<Factions>,
+    ) {
+        target.0 = None;
+        let Ok((player_transform, player_velocity, player_faction)) = player.get_single() else {
+            return;
+        };
+
+        target.0 = ships
+            .iter()
+            .filter(|(_, ship, transform, velocity, faction)| {
+                factions.is_hostile(*player_faction, **faction)
+                    && ship.health.fraction() < BOARDABLE_HULL
+                    && transform.translation.distance(player_transform.translation) < BOARDING_RANGE
+                    && (velocity.linvel - player_velocity.linvel).length() < BOARDING_SPEED
+            })
+            .min_by(|(_, _, a, _, _), (_, _, b, _, _)| {
+                let a = a.translation.distance(player_transform.translation);
+                let b = b.translation.distance(player_transform.translation);
+                a.total_cmp(&b)
+            })
+            .map(|(entity, _, _, _, _)| entity);
+    }
+
+    /// Sends the crew across to the boarding target. Both sides take losses, and if the boarders carry the day the
+    /// ship is captured and joins the player's faction, with a prize crew from the player's ship fighting her for them.
+    /// A ship whose whole crew is lost can neither sail nor fire.
+    pub fn board(
+        mut commands: Commands,
+        actions: Actions,
+        target: Res<BoardingTarget>,
+        mut player: Query<(&mut Ship, &Faction), Boarder>,
+        mut ships: Query<Prize, Boardable>,
+        factions: Res<Factions>,
+        mut captured: EventWriter<ShipCaptured>,
+    ) {
+        if !actions.just_pressed(Action::Board) {
+            return;
+        }
+        let Some(target) = target.0 else {
+            println!("There's no ship close enough to board");
+            return;
+        };
+        let Ok((mut player, player_faction)) = player.get_single_mut() else {
+            return;
+        };
+        let Ok((mut enemy, mut faction, mut ai, transform, name)) = ships.get_mut(target) else {
+            return;
+        };
+
+        let attack = boarding_strength(&player);
+        let defence = boarding_strength(&enemy);
+        player.crew.take_damage(defence * BOARDING_CASUALTIES);
+        enemy.crew.take_damage(attack * BOARDING_CASUALTIES);
+        if attack <= defence {
+            println!("Our boarding party was driven back from {}!", name.get());
+            if player.crew.is_dead() {
+                println!("Not a soul is left aboard to sail her...");
+            }
+            return;
+        }
+
+        println!(
+            "{} has struck her {} colours! 🏴‍☠️",
+            name.get(),
+            factions.name(*faction).to_lowercase()
+        );
+        // Her surviving defenders are taken prisoner, and some of the boarders stay behind to sail her
+        let prize_crew = player.crew.current() * PRIZE_CREW_SHARE;
+        player.crew.take_damage(prize_crew);
+        enemy.crew.set_current(prize_crew);
+        *faction = *player_faction;
+        // The prize crew patrols where she was taken
+        *ai = ShipAi::new(transform.translation);
+        commands
+            .entity(target)
+            .insert(collider_group::ship_collision_groups(*faction));
+        captured.send(ShipCaptured { entity: target });
+    }
+
+    /// Moves the player across to the nearest ship of their own faction, with the camera following them over.
+    /// The AI takes the helm of the ship left behind.
+    pub fn switch_command(
+        mut commands: Commands,
+        actions: Actions,
+        mut player: Query<Command, Boarder>,
+        mut ships: Query<Command, Boardable>,
+    ) {
+        if !actions.just_pressed(Action::SwitchShip) {
+            return;
+        }
+        let Ok((player_entity, player_transform, mut player_marker, player_faction, player_name)) =
+            player.get_single_mut()
+        else {
+            return;
+        };
+        let Some((ally_entity, _, mut ally_marker, _, ally_name)) = ships
+            .iter_mut()
+            .filter(|(_, _, _, faction, _)| *faction == player_faction)
+            .min_by(|(_, a, _, _, _), (_, b, _, _, _)| {
+                let a = a.translation.distance(player_transform.translation);
+                let b = b.translation.distance(player_transform.translation);
+                a.total_cmp(&b)
+            })
+        else {
+            println!("There's no allied ship to take command of");
+            return;
+        };
+
+        println!(
+            "Leaving {} to take command of {}",
+            player_name.get(),
+            ally_name.get()
+        );
+        *player_marker = ShipMarker::Ai;
+        *ally_marker = ShipMarker::Player;
+        commands
+            .entity(player_entity)
+            .remove::<PlayerShip>()
+            .insert(ShipAi::new(player_transform.translation));
+        commands
+            .entity(ally_entity)
+            .remove::<ShipAi>()
+            .insert(PlayerShip);
+    }
+}
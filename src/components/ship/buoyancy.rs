@@ -0,0 +1,64 @@
+use super::sinking::Sinking;
+use super::Ship;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Collider, Velocity};
+use bevy_water::WaterParam;
+
+// Distance from the ship's center to the bow and stern, and to either side of the hull,
+// for hulls that aren't a box
+const HULL_HALF_LENGTH: f32 = 10.0;
+const HULL_HALF_BEAM: f32 = 8.0;
+// How far below the water's surface the ship's origin sits when at rest
+const DRAFT: f32 = 1.0;
+// How quickly the ship rises and falls to meet the waves. Higher is stiffer.
+// Ships are heavily damped, so this needs to be higher than it looks.
+const HEAVE_RESPONSE: f32 = 4.0;
+// How quickly the ship pitches and rolls to match the waves. Higher is stiffer.
+const TILT_RESPONSE: f32 = 1.5;
+
+/// Projects a direction onto the water plane, so sample points don't move as the ship tilts.
+fn flatten(direction: Vec3) -> Vec3 {
+    Vec3::new(direction.x, 0.0, direction.z).normalize_or_zero()
+}
+
+type FloatingShip = (With<Ship>, Without<Sinking>);
+
+pub struct Systems;
+impl Systems {
+    /// Samples the waves under the bow, stern and either side of the hull,
+    /// then pushes the ship towards the height and tilt of the water beneath it.
+    /// Runs after the helm has set the ship's velocity, only adding the motion the helm doesn't control.
+    pub fn float(
+        mut ships: Query<(&Transform, &Collider, &mut Velocity), FloatingShip>,
+        water: WaterParam,
+    ) {
+        for (transform, collider, mut velocity) in ships.iter_mut() {
+            let (half_beam, half_length) = match collider.as_cuboid() {
+                Some(hull) => (hull.half_extents().x, hull.half_extents().z),
+                None => (HULL_HALF_BEAM, HULL_HALF_LENGTH),
+            };
+            let position = transform.translation;
+            let forward = flatten(transform.forward());
+            let right = flatten(transform.right());
+
+            let bow = water.wave_height(position + forward * half_length);
+            let stern = water.wave_height(position - forward * half_length);
+            let starboard = water.wave_height(position + right * half_beam);
+            let port = water.wave_height(position - right * half_beam);
+
+            // Heave
+            let target_height = (bow + stern + starboard + port) / 4.0 - DRAFT;
+            velocity.linvel.y = (target_height - position.y) * HEAVE_RESPONSE;
+
+            // Pitch and roll, by lining the ship's up axis up with the plane through the samples
+            let bow_slope = (bow - stern) / (half_length * 2.0);
+            let side_slope = (starboard - port) / (half_beam * 2.0);
+            let target_up = (Vec3::Y - forward * bow_slope - right * side_slope).normalize();
+            let up = transform.up();
+            let axis = up.cross(target_up);
+            let angle = up.angle_between(target_up);
+            let tilt = axis.normalize_or_zero() * angle * TILT_RESPONSE;
+            velocity.angvel = Vec3::new(tilt.x, velocity.angvel.y, tilt.z);
+        }
+    }
+}
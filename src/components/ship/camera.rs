@@ -1,23 +1,206 @@
-use bevy::prelude::*;
+use super::cannons::{self, CannonDirection, KillingShot};
+use super::sinking::ShipDestroyed;
+use super::{fleet::FleetMenu, PlayerShip, Ship};
+use crate::components::{
+    controls::{Action, Actions},
+    id::Name,
+    session::SessionEntity,
+};
+use bevy::{
+    ecs::system::SystemParam,
+    input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
 use bevy_atmosphere::prelude::AtmosphereCamera;
+use bevy_rapier3d::prelude::RapierConfiguration;
+use bevy_water::WaterParam;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
 
-pub const CAMERA_BASE_OFFSET: Vec3 = Vec3::new(0.0, 30.0, 60.0);
+const CAMERA_BASE_OFFSET: Vec3 = Vec3::new(0.0, 30.0, 60.0);
+// Field of view, in radians, when the camera isn't zoomed in on anything
+pub const CAMERA_FOV: f32 = FRAC_PI_4;
+// Zoom limits, as distance from the ship
+const MIN_CAMERA_DISTANCE: f32 = 20.0;
+const MAX_CAMERA_DISTANCE: f32 = 160.0;
+// Pitch limits, in radians above the horizon. Looking up from below deck level is left to the water floor to stop.
+const MIN_CAMERA_PITCH: f32 = -0.2;
+const MAX_CAMERA_PITCH: f32 = 1.4;
+// Fraction of the distance to the ship each line scrolled zooms by
+const CAMERA_ZOOM_STEP: f32 = 0.1;
+// How quickly the camera catches up with input, higher is snappier
+const CAMERA_SMOOTHING: f32 = 10.0;
+// Height above the ship's origin the camera looks at
+const CAMERA_FOCUS_HEIGHT: f32 = 10.0;
+// Closest the camera comes to the surface of the waves
+const CAMERA_WATER_CLEARANCE: f32 = 2.0;
+// How fast the spectator camera flies, in metres per second
+const FREE_FLY_SPEED: f32 = 40.0;
+// Keeps the spectator camera from flipping over when looking straight up or down
+const FREE_FLY_MAX_PITCH: f32 = FRAC_PI_2 - 0.05;
+// Speed the kill cam replays the final shot at, as a fraction of real time
+const KILL_CAM_SLOW_MOTION: f32 = 0.5;
+// How far behind and above the cannon ball the kill cam follows it
+const KILL_CAM_TRAIL: f32 = 12.0;
+const KILL_CAM_RISE: f32 = 3.0;
+// Once the ball strikes, the kill cam circles the wreck at this distance, height and speed in radians per second
+const KILL_CAM_ORBIT_DISTANCE: f32 = 60.0;
+const KILL_CAM_ORBIT_HEIGHT: f32 = 20.0;
+const KILL_CAM_ORBIT_SPEED: f32 = 0.15;
+// Radians the camera swings for each pixel the mouse moves
+const MOUSE_SPEED: f32 = 0.005;
+// Radians per second the camera swings with the right stick at full deflection
+const STICK_SPEED: f32 = 2.0;
+// Trackpads scroll in pixels rather than lines
+const PIXELS_PER_LINE: f32 = 50.0;
 
 #[derive(Component)]
 pub struct ShipCamera;
 
+/// What the ship camera is looking at.
+#[derive(Resource, Clone, Copy, PartialEq, Debug, Default)]
+pub enum CameraMode {
+    // Orbiting the player's ship
+    #[default]
+    Chase,
+    // Spectating, orbiting any ship at sea
+    Follow(Entity),
+    // Spectating, flying around freely
+    FreeFly,
+    // Replaying the shot that ended the voyage, see `KillCam`
+    KillCam,
+}
+
+impl CameraMode {
+    pub fn is_spectating(&self) -> bool {
+        matches!(self, Self::Follow(_) | Self::FreeFly)
+    }
+}
+
+/// Run condition for the player's controls that the spectator camera borrows.
+pub fn spectating(mode: Res<CameraMode>) -> bool {
+    mode.is_spectating()
+}
+
+/// A ship as she was sunk, and the cannon ball that did it if there was one.
+#[derive(Clone, Copy)]
+struct Kill {
+    ship: Entity,
+    position: Vec3,
+    shot: Option<KillingShot>,
+}
+
+/// Shown on the game over and victory screens: the final shot is replayed in slow motion with the rest of the
+/// world held still, then the camera circles the wreck as she goes down.
+#[derive(Resource, Default)]
+pub struct KillCam {
+    // The most recent ship to be sunk
+    last_kill: Option<Kill>,
+    // What the kill cam shows, once it's started
+    focus: Vec3,
+    shot: Option<KillingShot>,
+    // Seconds since it started, in real time as the game clock is stopped during the replay
+    elapsed: f32,
+}
+
+/// Where the camera sits around the ship, in angles relative to the ship's heading.
+/// Input moves the targets, and the camera eases towards them.
+#[derive(Component)]
+pub struct OrbitCamera {
+    pub yaw: f32,
+    pub pitch: f32,
+    pub distance: f32,
+    pub target_yaw: f32,
+    pub target_pitch: f32,
+    pub target_distance: f32,
+    // How far the camera has swung over to the aiming view, from 0 (orbiting) to 1
+    pub aiming: f32,
+    // Side and landing point of the last broadside aimed, kept so the camera can ease back from it
+    pub aim: Option<(CannonDirection, Vec3)>,
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        // Starts out astern of the ship, where the fixed camera used to be
+        let pitch = CAMERA_BASE_OFFSET.y.atan2(CAMERA_BASE_OFFSET.z);
+        let distance = CAMERA_BASE_OFFSET.length();
+        Self {
+            yaw: 0.0,
+            pitch,
+            distance,
+            target_yaw: 0.0,
+            target_pitch: pitch,
+            target_distance: distance,
+            aiming: 0.0,
+            aim: None,
+        }
+    }
+}
+
+impl OrbitCamera {
+    /// Offset from the point the camera looks at, in world space, for a ship with the given heading.
+    pub fn offset(&self, ship_yaw: f32) -> Vec3 {
+        let yaw = ship_yaw + self.yaw;
+        Vec3::new(
+            yaw.sin() * self.pitch.cos(),
+            self.pitch.sin(),
+            yaw.cos() * self.pitch.cos(),
+        ) * self.distance
+    }
+
+    /// Moves the targets by the input's swing and zoom, then eases the camera towards them.
+    pub fn steer(&mut self, swing: Vec2, scroll: f32, delta_seconds: f32) {
+        self.target_yaw += swing.x;
+        self.target_pitch = (self.target_pitch + swing.y).clamp(MIN_CAMERA_PITCH, MAX_CAMERA_PITCH);
+        self.target_distance = (self.target_distance * (1.0 - scroll * CAMERA_ZOOM_STEP))
+            .clamp(MIN_CAMERA_DISTANCE, MAX_CAMERA_DISTANCE);
+
+        let smoothing = 1.0 - (-CAMERA_SMOOTHING * delta_seconds).exp();
+        self.yaw += (self.target_yaw - self.yaw) * smoothing;
+        self.pitch += (self.target_pitch - self.pitch) * smoothing;
+        self.distance += (self.target_distance - self.distance) * smoothing;
+    }
+
+    /// The camera's place in the world, orbiting the given ship.
+    /// Worked out in world space, so the camera doesn't roll with the ship and can be kept above the waves.
+    pub fn view(&self, ship: &Transform, water: &WaterParam) -> Transform {
+        let (ship_yaw, _, _) = ship.rotation.to_euler(EulerRot::YXZ);
+        let focus = ship.translation + Vec3::Y * CAMERA_FOCUS_HEIGHT;
+        let mut position = focus + self.offset(ship_yaw);
+        position.y = position
+            .y
+            .max(water.wave_height(position) + CAMERA_WATER_CLEARANCE);
+        Transform::from_translation(position).looking_at(focus, Vec3::Y)
+    }
+
+    /// Drops any aiming view straight away, for when another mode takes over the camera.
+    fn stop_aiming(&mut self, projection: &mut Projection) {
+        self.aiming = 0.0;
+        self.aim = None;
+        if let Projection::Perspective(perspective) = projection {
+            perspective.fov = CAMERA_FOV;
+        }
+    }
+}
+
+/// Spawned once for each voyage, apart from any ship so it can look elsewhere once the player's has sunk.
 #[derive(Bundle)]
 pub struct ShipCameraBundle {
+    session: SessionEntity,
     marker: ShipCamera,
+    orbit: OrbitCamera,
     camera: Camera3dBundle,
     atmosphere: AtmosphereCamera,
     fog: FogSettings,
+    // UI is drawn by its own camera, so it survives the ship camera being despawned
+    ui: UiCameraConfig,
 }
 
 impl Default for ShipCameraBundle {
     fn default() -> Self {
         Self {
+            session: SessionEntity,
             marker: ShipCamera,
+            orbit: OrbitCamera::default(),
             camera: Camera3dBundle {
                 transform: Transform::from_translation(CAMERA_BASE_OFFSET)
                     .looking_at(Vec3::ZERO, Vec3::Y),
@@ -29,6 +212,254 @@ impl Default for ShipCameraBundle {
                 falloff: FogFalloff::Exponential { density: 0.0003 },
                 ..default()
             },
+            ui: UiCameraConfig { show_ui: false },
         }
     }
 }
+
+/// Mouse, scroll wheel and right stick input for swinging the camera around and zooming.
+#[derive(SystemParam)]
+pub struct CameraInput<'w, 's> {
+    motion: EventReader<'w, 's, MouseMotion>,
+    wheel: EventReader<'w, 's, MouseWheel>,
+    actions: Actions<'w>,
+    fleet_menu: Res<'w, FleetMenu>,
+}
+
+impl<'w, 's> CameraInput<'w, 's> {
+    /// Radians to swing the camera by this frame, in yaw and pitch.
+    /// The mouse and right stick pick fleet commands instead while the fleet menu is open.
+    pub fn swing(&mut self, delta_seconds: f32) -> Vec2 {
+        let motion: Vec2 = self.motion.iter().map(|motion| motion.delta).sum();
+        if self.fleet_menu.is_open() {
+            return Vec2::ZERO;
+        }
+        let look = self.actions.look() * STICK_SPEED * delta_seconds;
+        // Stick y points up, where mouse motion points down
+        Vec2::new(
+            -(motion.x * MOUSE_SPEED + look.x),
+            motion.y * MOUSE_SPEED - look.y,
+        )
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.actions.just_pressed(action)
+    }
+
+    /// See `Actions::fly`.
+    pub fn fly(&self) -> Vec3 {
+        self.actions.fly()
+    }
+
+    /// Lines scrolled, positive away from the player.
+    pub fn scroll(&mut self) -> f32 {
+        self.wheel
+            .iter()
+            .map(|wheel| match wheel.unit {
+                MouseScrollUnit::Line => wheel.y,
+                MouseScrollUnit::Pixel => wheel.y / PIXELS_PER_LINE,
+            })
+            .sum()
+    }
+}
+
+type SpectatorCamera<'a> = (&'a mut Transform, &'a mut Projection, &'a mut OrbitCamera);
+type Spectated = (With<Ship>, Without<ShipCamera>);
+
+pub struct Systems;
+impl Systems {
+    pub fn spawn_camera(mut commands: Commands) {
+        commands.spawn(ShipCameraBundle::default());
+        commands.insert_resource(CameraMode::default());
+        commands.insert_resource(KillCam::default());
+    }
+
+    /// Toggles the spectator camera, which orbits whichever ship it's following just like the chase camera, or flies
+    /// freely with the sailing controls. Switching ships cycles through every ship at sea, then back to flying.
+    pub fn spectate(
+        mut mode: ResMut<CameraMode>,
+        mut camera: Query<SpectatorCamera, With<ShipCamera>>,
+        ships: Query<(Entity, &Transform, &Name), Spectated>,
+        mut input: CameraInput,
+        water: WaterParam,
+        time: Res<Time>,
+    ) {
+        let Ok((mut camera, mut projection, mut orbit)) = camera.get_single_mut() else {
+            return;
+        };
+        if input.just_pressed(Action::Spectate) {
+            match *mode {
+                CameraMode::Chase => {
+                    println!("Spectating, switch ships to follow one 👀");
+                    orbit.stop_aiming(&mut projection);
+                    *mode = CameraMode::FreeFly;
+                }
+                CameraMode::Follow(_) | CameraMode::FreeFly => {
+                    println!("Back at the helm");
+                    *mode = CameraMode::Chase;
+                }
+                CameraMode::KillCam => {}
+            }
+        }
+        if !mode.is_spectating() {
+            return;
+        }
+
+        if input.just_pressed(Action::SwitchShip) {
+            // Ordered by entity, so each ship comes round in the same order every time
+            let mut following: Vec<(Entity, &Name)> = ships
+                .iter()
+                .map(|(entity, _, name)| (entity, name))
+                .collect();
+            following.sort_by_key(|(entity, _)| *entity);
+            let next = match *mode {
+                CameraMode::Follow(current) => following
+                    .iter()
+                    .position(|(entity, _)| *entity == current)
+                    .and_then(|index| following.get(index + 1)),
+                _ => following.first(),
+            };
+            *mode = match next {
+                Some((entity, name)) => {
+                    println!("Following {}", name.get());
+                    CameraMode::Follow(*entity)
+                }
+                None => {
+                    println!("Flying free");
+                    CameraMode::FreeFly
+                }
+            };
+        }
+
+        let swing = input.swing(time.delta_seconds());
+        let scroll = input.scroll();
+        match *mode {
+            CameraMode::Follow(entity) => {
+                let Ok((_, ship, _)) = ships.get(entity) else {
+                    // She's gone down, so carry on from wherever the camera was
+                    *mode = CameraMode::FreeFly;
+                    return;
+                };
+                orbit.steer(swing, scroll, time.delta_seconds());
+                *camera = orbit.view(ship, &water);
+            }
+            CameraMode::FreeFly => {
+                let (yaw, pitch, _) = camera.rotation.to_euler(EulerRot::YXZ);
+                // Moving the mouse down swings the orbit up, which looks further down
+                let pitch = (pitch - swing.y).clamp(-FREE_FLY_MAX_PITCH, FREE_FLY_MAX_PITCH);
+                camera.rotation = Quat::from_euler(EulerRot::YXZ, yaw + swing.x, pitch, 0.0);
+
+                let fly = input.fly();
+                let heading = camera.rotation * Vec3::new(fly.x, 0.0, -fly.z) + Vec3::Y * fly.y;
+                camera.translation += heading * FREE_FLY_SPEED * time.delta_seconds();
+                let floor = water.wave_height(camera.translation) + CAMERA_WATER_CLEARANCE;
+                camera.translation.y = camera.translation.y.max(floor);
+            }
+            _ => {}
+        }
+    }
+
+    /// Keeps track of the latest ship to be sunk, and the shot that sank her, for the kill cam.
+    pub fn record_kills(
+        mut kill_cam: ResMut<KillCam>,
+        mut destroyed: EventReader<ShipDestroyed>,
+        mut killing_shots: EventReader<KillingShot>,
+        ships: Query<&Transform>,
+    ) {
+        let shots: Vec<KillingShot> = killing_shots.iter().copied().collect();
+        for event in destroyed.iter() {
+            let Ok(transform) = ships.get(event.entity) else {
+                continue;
+            };
+            kill_cam.last_kill = Some(Kill {
+                ship: event.entity,
+                position: transform.translation,
+                shot: shots.iter().find(|shot| shot.ship == event.entity).copied(),
+            });
+        }
+    }
+
+    /// Rolls the kill cam when the voyage ends. A ship that's still going down is the star of the show, otherwise the
+    /// camera circles the player's ship, like after the last enemy strikes her colours.
+    pub fn start_kill_cam(
+        mut mode: ResMut<CameraMode>,
+        mut kill_cam: ResMut<KillCam>,
+        mut camera: Query<(&mut Projection, &mut OrbitCamera), With<ShipCamera>>,
+        ships: Query<&Transform, With<Ship>>,
+        player: Query<&Transform, With<PlayerShip>>,
+        mut time: ResMut<Time>,
+        mut rapier: ResMut<RapierConfiguration>,
+    ) {
+        let Ok((mut projection, mut orbit)) = camera.get_single_mut() else {
+            return;
+        };
+        orbit.stop_aiming(&mut projection);
+        *mode = CameraMode::KillCam;
+        kill_cam.elapsed = 0.0;
+
+        // Wrecks are cleared away once they've sunk, so an older kill won't be found
+        match kill_cam.last_kill.filter(|kill| ships.contains(kill.ship)) {
+            Some(kill) => {
+                kill_cam.focus = kill.position;
+                kill_cam.shot = kill.shot;
+            }
+            None => {
+                kill_cam.focus = player
+                    .get_single()
+                    .map(|transform| transform.translation)
+                    .unwrap_or_default();
+                kill_cam.shot = None;
+            }
+        }
+        if kill_cam.shot.is_some() {
+            // Held still until the replay catches up with the moment the ball struck
+            time.pause();
+            rapier.physics_pipeline_active = false;
+        }
+    }
+
+    pub fn kill_cam(
+        mut camera: Query<&mut Transform, With<ShipCamera>>,
+        mut kill_cam: ResMut<KillCam>,
+        mut time: ResMut<Time>,
+        mut rapier: ResMut<RapierConfiguration>,
+        mut gizmos: Gizmos,
+    ) {
+        let Ok(mut camera) = camera.get_single_mut() else {
+            return;
+        };
+        kill_cam.elapsed += time.raw_delta_seconds();
+
+        // The wreck is first seen from behind the ball that sank her, and from astern otherwise
+        let mut approach = Vec3::Z;
+        let mut replayed = 0.0;
+        if let Some(shot) = kill_cam.shot {
+            let gravity = cannons::cannon_gravity(&rapier);
+            let t = kill_cam.elapsed * KILL_CAM_SLOW_MOTION;
+            let ball = shot.position(t, gravity);
+            let travel = shot.position(t + 0.05, gravity) - shot.position(t - 0.05, gravity);
+            approach = Vec3::new(-travel.x, 0.0, -travel.z)
+                .try_normalize()
+                .unwrap_or(Vec3::Z);
+            if t < shot.flight {
+                let eye =
+                    ball - travel.normalize_or_zero() * KILL_CAM_TRAIL + Vec3::Y * KILL_CAM_RISE;
+                *camera = Transform::from_translation(eye).looking_at(ball, Vec3::Y);
+                gizmos.sphere(ball, Quat::IDENTITY, 1.0, Color::BLACK);
+                return;
+            }
+            if time.is_paused() {
+                time.unpause();
+                rapier.physics_pipeline_active = true;
+            }
+            replayed = shot.flight / KILL_CAM_SLOW_MOTION;
+        }
+
+        let angle =
+            approach.x.atan2(approach.z) + (kill_cam.elapsed - replayed) * KILL_CAM_ORBIT_SPEED;
+        let eye = kill_cam.focus
+            + Vec3::new(angle.sin(), 0.0, angle.cos()) * KILL_CAM_ORBIT_DISTANCE
+            + Vec3::Y * KILL_CAM_ORBIT_HEIGHT;
+        *camera = Transform::from_translation(eye).looking_at(kill_cam.focus, Vec3::Y);
+    }
+}
@@ -1,72 +1,304 @@
-use crate::components::collider_group::AsCollisionGroups;
+use super::{ammo::AmmoType, sections::Section, Ship};
+use crate::components::{id::Name, session::SessionEntity};
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
-use std::time::Duration;
+use bevy_water::WaterParam;
+use serde::Deserialize;
+use std::{f32::consts::TAU, time::Duration};
 
+// Muzzle velocity
 const CANNON_SPEED: f32 = 100.0;
+// Cannon balls fall faster than the rest of the world, so broadsides land within sight of the ship
+const CANNON_GRAVITY_SCALE: f32 = 3.0;
+// Elevation limits, in radians. A little depression lets cannons hit ships alongside.
+const MIN_ELEVATION: f32 = -0.09;
+const MAX_ELEVATION: f32 = 0.5;
+// Roughly the fixed angle cannons fired at before they could be aimed
+const DEFAULT_ELEVATION: f32 = 0.2;
+// Fallback for cannon balls that never reach the water, like ones stuck on top of a ship
+const CANNON_BALL_LIFETIME: f32 = 10.0;
 
-pub enum CannonMarker {
-    Player,
-    Enemy,
+/// A ship class' battery, see `ShipClass`.
+#[derive(Deserialize, Clone)]
+pub struct CannonConfig {
+    // Cannons on each side of the ship
+    pub count: u32,
+    // Distance between each cannon, along the length of the ship
+    pub spacing: f32,
+    // How far out from the centre line and up from the ship's origin the gun ports sit.
+    // High enough that balls don't launch below the water line.
+    pub port_offset: (f32, f32),
+    // Seconds
+    pub reload_time: f32,
+    // Most each ball can stray from where the cannons are laid, in radians
+    pub dispersion: f32,
+}
+
+impl Default for CannonConfig {
+    fn default() -> Self {
+        Self {
+            count: 3,
+            spacing: 5.0,
+            port_offset: (8.0, 2.0),
+            reload_time: 3.0,
+            dispersion: 0.02,
+        }
+    }
 }
 
 #[derive(Component)]
 pub struct Cannons {
-    marker: CannonMarker,
-    reload_speed: u64,
+    count: u32,
+    spacing: f32,
+    port_offset: (f32, f32),
+    dispersion: f32,
+    reload_time: Duration,
     left_last_launched: Duration,
     right_last_launched: Duration,
+    // How long each side takes to reload after its last broadside
+    left_reload_time: Duration,
+    right_reload_time: Duration,
+    left_elevation: f32,
+    right_elevation: f32,
 }
 
 impl Default for Cannons {
     fn default() -> Self {
-        Self {
-            marker: CannonMarker::Player,
-            reload_speed: 3,
+        Self::new(&CannonConfig::default())
+    }
+}
+
+impl Cannons {
+    pub fn new(config: &CannonConfig) -> Self {
+        let mut cannons = Self {
+            count: 0,
+            spacing: 0.0,
+            port_offset: (0.0, 0.0),
+            dispersion: 0.0,
+            reload_time: Duration::ZERO,
             left_last_launched: Duration::from_secs(0),
             right_last_launched: Duration::from_secs(0),
+            left_reload_time: Duration::ZERO,
+            right_reload_time: Duration::ZERO,
+            left_elevation: DEFAULT_ELEVATION,
+            right_elevation: DEFAULT_ELEVATION,
+        };
+        cannons.configure(config);
+        cannons
+    }
+
+    /// Applies a battery's stats without resetting reload progress.
+    pub fn configure(&mut self, config: &CannonConfig) {
+        self.count = config.count;
+        self.spacing = config.spacing;
+        self.port_offset = config.port_offset;
+        self.dispersion = config.dispersion;
+        self.reload_time = Duration::from_secs_f32(config.reload_time);
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    pub fn reload_time(&self) -> Duration {
+        self.reload_time
+    }
+
+    fn last_launched(&self, direction: CannonDirection) -> Duration {
+        match direction {
+            CannonDirection::Left => self.left_last_launched,
+            CannonDirection::Right => self.right_last_launched,
         }
     }
-}
 
-impl Cannons {
-    pub fn fire(
+    /// How long this side takes to reload from its last broadside.
+    pub fn side_reload_time(&self, direction: CannonDirection) -> Duration {
+        match direction {
+            CannonDirection::Left => self.left_reload_time,
+            CannonDirection::Right => self.right_reload_time,
+        }
+    }
+
+    /// Time since this side last fired, capped at the reload time
+    pub fn reloading_for(&self, time_elapsed: Duration, direction: CannonDirection) -> Duration {
+        time_elapsed
+            .saturating_sub(self.last_launched(direction))
+            .min(self.side_reload_time(direction))
+    }
+
+    /// Rewinds this side's last launch so it has been reloading for `reloading_for` out of `reload_time`, such as when restoring a saved game.
+    pub fn set_reloading_for(
         &mut self,
-        commands: &mut Commands,
         time_elapsed: Duration,
-        ship_transform: &Transform,
         direction: CannonDirection,
+        reloading_for: Duration,
+        reload_time: Duration,
     ) {
-        // Check if this direction's cannons are still being reloaded
-        // If they have already been reloaded, mark this direction as launched.
+        let last_launched = time_elapsed.saturating_sub(reloading_for);
         match direction {
             CannonDirection::Left => {
-                if time_elapsed < self.left_last_launched + Duration::from_secs(self.reload_speed) {
-                    println!("Reloading these cannons! 🏴‍☠️");
-                    return;
-                } else {
-                    self.left_last_launched = time_elapsed;
-                }
+                self.left_last_launched = last_launched;
+                self.left_reload_time = reload_time;
             }
             CannonDirection::Right => {
-                if time_elapsed < self.right_last_launched + Duration::from_secs(self.reload_speed)
-                {
-                    println!("Reloading these cannons! 🏴‍☠️");
-                    return;
-                } else {
-                    self.right_last_launched = time_elapsed;
-                }
+                self.right_last_launched = last_launched;
+                self.right_reload_time = reload_time;
             }
         }
-        let instance = CannonBall::instance(&self.marker, ship_transform, direction);
-        commands.spawn(CannonBall::adjust_fire_location(instance.clone(), -10.0));
-        commands.spawn(CannonBall::adjust_fire_location(instance.clone(), -5.0));
-        commands.spawn(instance);
+    }
+
+    pub fn is_loaded(&self, time_elapsed: Duration, direction: CannonDirection) -> bool {
+        time_elapsed >= self.last_launched(direction) + self.side_reload_time(direction)
+    }
+
+    /// How far through reloading this side's cannons are, from 0.0 (just fired) to 1.0 (ready to fire)
+    pub fn reload_progress(&self, time_elapsed: Duration, direction: CannonDirection) -> f32 {
+        let reload_time = self.side_reload_time(direction);
+        if reload_time.is_zero() {
+            return 1.0;
+        }
+        let reloading_for = self.reloading_for(time_elapsed, direction).as_secs_f32();
+        (reloading_for / reload_time.as_secs_f32()).min(1.0)
+    }
+
+    /// Angle above the horizon this side's cannons fire at, in radians.
+    pub fn elevation(&self, direction: CannonDirection) -> f32 {
+        match direction {
+            CannonDirection::Left => self.left_elevation,
+            CannonDirection::Right => self.right_elevation,
+        }
+    }
+
+    pub fn set_elevation(&mut self, direction: CannonDirection, elevation: f32) {
+        let elevation = elevation.clamp(MIN_ELEVATION, MAX_ELEVATION);
+        match direction {
+            CannonDirection::Left => self.left_elevation = elevation,
+            CannonDirection::Right => self.right_elevation = elevation,
+        }
+    }
+
+    /// Elevation that lands a ball `range` away on flat water, or the highest elevation if that's out of range.
+    pub fn elevation_for_range(range: f32, gravity: Vec3) -> f32 {
+        let sin = (range * gravity.length() / CANNON_SPEED.powi(2)).min(1.0);
+        (0.5 * sin.asin()).clamp(MIN_ELEVATION, MAX_ELEVATION)
+    }
+
+    /// Where a gun port `along` the length of the ship sits in the world, forward being negative.
+    fn port_position(
+        &self,
+        ship_transform: &Transform,
+        direction: CannonDirection,
+        along: f32,
+    ) -> Vec3 {
+        let (out, up) = self.port_offset;
+        ship_transform.transform_point(Vec3::new(out * direction.as_linvel(), up, along))
+    }
+
+    /// Middle of this side's gun ports, where the broadside's aim is taken from.
+    pub fn launch_position(&self, ship_transform: &Transform, direction: CannonDirection) -> Vec3 {
+        self.port_position(ship_transform, direction, 0.0)
+    }
+
+    pub fn launch_velocity(&self, ship_transform: &Transform, direction: CannonDirection) -> Vec3 {
+        let elevation = self.elevation(direction);
+        let broadside = ship_transform.local_x() * direction.as_linvel();
+        (broadside * elevation.cos() + Vec3::Y * elevation.sin()) * CANNON_SPEED
+    }
+
+    /// Launches a broadside and starts this side reloading, see `Ship::fire` for the checks that come first.
+    /// Balls leave the gun ports, spread evenly along the ship, carrying the ship's own velocity with them.
+    pub fn fire(
+        &mut self,
+        commands: &mut Commands,
+        time_elapsed: Duration,
+        ship_transform: &Transform,
+        ship_linvel: Vec3,
+        direction: CannonDirection,
+        broadside: Broadside,
+    ) {
+        self.set_reloading_for(
+            time_elapsed,
+            direction,
+            Duration::ZERO,
+            broadside.reload_time,
+        );
+        let launch_velocity = self.launch_velocity(ship_transform, direction);
+        let first_port = -(self.count.saturating_sub(1) as f32) * self.spacing / 2.0;
+        for cannon in 0..broadside.balls {
+            let along = first_port + cannon as f32 * self.spacing;
+            commands.spawn(CannonBallBundle::instance(
+                broadside.collision_groups,
+                broadside.ammo,
+                self.port_position(ship_transform, direction, along),
+                disperse(launch_velocity, self.dispersion) + ship_linvel,
+            ));
+        }
+    }
+}
+
+/// A single broadside, once the ship has checked it's loaded and has the ammo for it.
+pub struct Broadside {
+    pub ammo: AmmoType,
+    // Cannons with a ball to fire, fewer than the full battery once the ammo runs low
+    pub balls: u32,
+    pub reload_time: Duration,
+    // Decides which ships the balls can hit, see `collider_group::cannon_collision_groups`
+    pub collision_groups: CollisionGroups,
+}
+
+#[derive(Component, Clone)]
+pub struct CannonBall {
+    ammo: AmmoType,
+    lifetime: Timer,
+    // Where and how fast the ball left the gun, so the shot can be replayed
+    launch: Vec3,
+    launch_linvel: Vec3,
+}
+
+impl CannonBall {
+    fn new(ammo: AmmoType, launch: Vec3, launch_linvel: Vec3) -> Self {
+        Self {
+            ammo,
+            lifetime: Timer::from_seconds(CANNON_BALL_LIFETIME, TimerMode::Once),
+            launch,
+            launch_linvel,
+        }
+    }
+}
+
+#[derive(Event)]
+pub struct CannonSplash {
+    pub position: Vec3,
+}
+
+/// The cannon ball that took the last of a ship's hull, from the moment it was fired to where it struck.
+#[derive(Event, Clone, Copy)]
+pub struct KillingShot {
+    pub ship: Entity,
+    pub launch: Vec3,
+    pub launch_linvel: Vec3,
+    pub impact: Vec3,
+    // Seconds the ball was in the air
+    pub flight: f32,
+}
+
+impl KillingShot {
+    /// Where the ball was `t` seconds into its flight.
+    /// Follows the same arc as `trajectory`, bent slightly so it always ends where the ball really struck.
+    pub fn position(&self, t: f32, gravity: Vec3) -> Vec3 {
+        let arc = |t: f32| self.launch + self.launch_linvel * t + 0.5 * gravity * t * t;
+        if self.flight <= 0.0 {
+            return self.impact;
+        }
+        let t = t.clamp(0.0, self.flight);
+        arc(t) + (self.impact - arc(self.flight)) * (t / self.flight)
     }
 }
 
 #[derive(Bundle, Clone)]
-struct CannonBall {
+struct CannonBallBundle {
+    session: SessionEntity,
+    cannon_ball: CannonBall,
     rigidbody: RigidBody,
     collider: Collider,
     restitution: Restitution,
@@ -74,15 +306,17 @@ struct CannonBall {
     transform: TransformBundle,
     gravity: GravityScale,
     collision_group: CollisionGroups,
+    active_events: ActiveEvents,
 }
 
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum CannonDirection {
     Left,
     Right,
 }
 
 impl CannonDirection {
-    fn as_linvel(&self) -> f32 {
+    pub fn as_linvel(&self) -> f32 {
         match self {
             Self::Left => -1.0,
             Self::Right => 1.0,
@@ -90,32 +324,167 @@ impl CannonDirection {
     }
 }
 
-impl CannonBall {
+impl CannonBallBundle {
     fn instance(
-        cannon_marker: &CannonMarker,
-        ship_transform: &Transform,
-        direction: CannonDirection,
+        collision_groups: CollisionGroups,
+        ammo: AmmoType,
+        position: Vec3,
+        linvel: Vec3,
     ) -> Self {
-        let mut transform = TransformBundle::from(ship_transform.clone());
-        // Adjust launch height so they don't launch below the water line
-        transform.local.translation += Vec3::new(0.0, 2.0, 0.0);
         Self {
+            session: SessionEntity,
+            cannon_ball: CannonBall::new(ammo, position, linvel),
             rigidbody: RigidBody::Dynamic,
             collider: Collider::ball(1.0),
             restitution: Restitution::coefficient(0.7),
             velocity: Velocity {
-                linvel: ship_transform.local_x() * CANNON_SPEED * direction.as_linvel()
-                    + (Vec3::Y * 20.0),
+                linvel,
                 angvel: Vec3::ZERO,
             },
-            transform,
-            gravity: GravityScale(3.0),
-            collision_group: cannon_marker.as_collision_groups(),
+            transform: TransformBundle::from(Transform::from_translation(position)),
+            gravity: GravityScale(CANNON_GRAVITY_SCALE),
+            collision_group: collision_groups,
+            // Required for Rapier to report contacts with ships
+            active_events: ActiveEvents::COLLISION_EVENTS,
+        }
+    }
+}
+
+/// Turns a ball's velocity up to `dispersion` radians off true, in a random direction.
+fn disperse(linvel: Vec3, dispersion: f32) -> Vec3 {
+    let Some(direction) = linvel.try_normalize() else {
+        return linvel;
+    };
+    let axis = Quat::from_axis_angle(direction, fastrand::f32() * TAU)
+        * direction.any_orthonormal_vector();
+    Quat::from_axis_angle(axis, fastrand::f32() * dispersion) * linvel
+}
+
+/// Gravity as felt by cannon balls.
+pub fn cannon_gravity(rapier: &RapierConfiguration) -> Vec3 {
+    rapier.gravity * CANNON_GRAVITY_SCALE
+}
+
+/// Points along a cannon ball's flight, ending where it meets the water.
+pub fn trajectory(position: Vec3, linvel: Vec3, gravity: Vec3, water: &WaterParam) -> Vec<Vec3> {
+    const STEP: f32 = 0.05;
+    let mut points = vec![position];
+    let mut t = STEP;
+    while t < CANNON_BALL_LIFETIME {
+        let point = position + linvel * t + 0.5 * gravity * t * t;
+        points.push(point);
+        let falling = linvel.y + gravity.y * t < 0.0;
+        if falling && point.y < water.wave_height(point) {
+            break;
         }
+        t += STEP;
     }
+    points
+}
 
-    fn adjust_fire_location(mut cannon_ball: Self, amount: f32) -> Self {
-        cannon_ball.transform.local.translation += Vec3::new(0.0, 0.0, amount);
-        cannon_ball
+pub struct Systems;
+impl Systems {
+    /// Applies a cannon ball's damage to any ship it comes into contact with, at the section nearest where it struck.
+    /// Friendly fire is filtered out by the collision groups, so any contact reported here is a hit.
+    pub fn hit_detection(
+        mut commands: Commands,
+        mut collisions: EventReader<CollisionEvent>,
+        cannon_balls: Query<(&CannonBall, &Transform)>,
+        mut ships: Query<(&mut Ship, &Transform, &Collider, &Name)>,
+        mut killing_shots: EventWriter<KillingShot>,
+    ) {
+        let mut spent: Vec<Entity> = Vec::new();
+        for collision in collisions.iter() {
+            let CollisionEvent::Started(a, b, _) = collision else {
+                continue;
+            };
+            let (ball_entity, ship_entity) = if cannon_balls.contains(*a) {
+                (*a, *b)
+            } else if cannon_balls.contains(*b) {
+                (*b, *a)
+            } else {
+                continue;
+            };
+            // A ball can touch more than one ship in the same frame, only the first one counts.
+            if spent.contains(&ball_entity) {
+                continue;
+            }
+            let Ok((mut ship, ship_transform, collider, name)) = ships.get_mut(ship_entity) else {
+                continue;
+            };
+            let Some(hull) = collider.as_cuboid() else {
+                continue;
+            };
+
+            let (cannon_ball, ball_transform) = cannon_balls.get(ball_entity).unwrap();
+            let section = Section::hit(
+                ship_transform,
+                hull.half_extents(),
+                ball_transform.translation,
+            );
+            let afloat = !ship.health.is_dead();
+            let standing: Vec<Section> = Section::ALL
+                .into_iter()
+                .filter(|section| !ship.section(*section).is_dead())
+                .collect();
+            let manned = !ship.crew.is_dead();
+            ship.take_hit(cannon_ball.ammo, section);
+            // A sunk hull is announced as the ship goes down
+            for section in standing {
+                if section != Section::Hull && ship.section(section).is_dead() {
+                    let verb = if section == Section::Masts {
+                        "have"
+                    } else {
+                        "has"
+                    };
+                    println!(
+                        "{}'s {} {} been shot away!",
+                        name.get(),
+                        section.label(),
+                        verb
+                    );
+                }
+            }
+            if manned && ship.crew.is_dead() {
+                println!("{}'s crew has been cut down!", name.get());
+            }
+            if afloat && ship.health.is_dead() {
+                killing_shots.send(KillingShot {
+                    ship: ship_entity,
+                    launch: cannon_ball.launch,
+                    launch_linvel: cannon_ball.launch_linvel,
+                    impact: ball_transform.translation,
+                    flight: cannon_ball.lifetime.elapsed_secs(),
+                });
+            }
+            commands.entity(ball_entity).despawn();
+            spent.push(ball_entity);
+        }
+    }
+
+    /// Removes cannon balls once they fall into the sea, or once they've been around for too long.
+    pub fn splash_detection(
+        mut commands: Commands,
+        mut cannon_balls: Query<(Entity, &Transform, &Velocity, &mut CannonBall)>,
+        mut splashes: EventWriter<CannonSplash>,
+        water: WaterParam,
+        time: Res<Time>,
+    ) {
+        for (entity, transform, velocity, mut cannon_ball) in cannon_balls.iter_mut() {
+            cannon_ball.lifetime.tick(time.delta());
+            if cannon_ball.lifetime.finished() {
+                commands.entity(entity).despawn();
+                continue;
+            }
+
+            // Cannon balls can be launched from below the crest of a wave, so only count them once they're falling
+            let position = transform.translation;
+            if velocity.linvel.y < 0.0 && position.y < water.wave_height(position) {
+                splashes.send(CannonSplash {
+                    position: water.wave_point(position),
+                });
+                commands.entity(entity).despawn();
+            }
+        }
     }
 }
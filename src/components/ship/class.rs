@@ -0,0 +1,126 @@
+use super::{ammo::AmmoConfig, cannons::CannonConfig, sails::SailSpeeds, Ship};
+use crate::components::id::Name;
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
+use bevy_rapier3d::prelude::{AdditionalMassProperties, Collider, Damping};
+use serde::Deserialize;
+
+/// Stats shared by every ship of a kind, loaded from a `.ship.ron` file in `assets/ships`.
+#[derive(Deserialize, TypeUuid, TypePath)]
+#[uuid = "2091fa17-a159-408a-9344-0e0b0e196d0c"]
+pub struct ShipClass {
+    // Asset path of the ship's scene
+    pub model: String,
+    #[serde(skip)]
+    pub scene: Handle<Scene>,
+    // Half extents of the hull's box collider
+    pub collider: (f32, f32, f32),
+    pub mass: f32,
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    // Health of the hull
+    pub health: f32,
+    pub masts: f32,
+    pub rudder: f32,
+    // Health of each side's battery
+    pub battery: f32,
+    pub crew: f32,
+    pub cannons: CannonConfig,
+    pub ammo: AmmoConfig,
+    pub repair_supplies: f32,
+    pub sail_speeds: SailSpeeds,
+}
+
+impl ShipClass {
+    pub fn collider(&self) -> Collider {
+        let (x, y, z) = self.collider;
+        Collider::cuboid(x, y, z)
+    }
+
+    pub fn mass(&self) -> AdditionalMassProperties {
+        AdditionalMassProperties::Mass(self.mass)
+    }
+
+    pub fn damping(&self) -> Damping {
+        Damping {
+            linear_damping: self.linear_damping,
+            angular_damping: self.angular_damping,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ShipClassLoader;
+
+impl AssetLoader for ShipClassLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let mut class: ShipClass = ron::de::from_bytes(bytes)?;
+            let model_path = AssetPath::from(class.model.as_str()).to_owned();
+            class.scene = load_context.get_handle(model_path.get_id());
+            load_context.set_default_asset(LoadedAsset::new(class).with_dependency(model_path));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ship.ron"]
+    }
+}
+
+type ClassedShip<'a> = (
+    &'a Name,
+    &'a Handle<ShipClass>,
+    &'a mut Ship,
+    &'a mut Collider,
+    &'a mut AdditionalMassProperties,
+    &'a mut Damping,
+    &'a Children,
+);
+
+pub struct Systems;
+impl Systems {
+    /// Applies edits to a ship class file to every ship of that class already at sea.
+    pub fn hot_reload(
+        mut events: EventReader<AssetEvent<ShipClass>>,
+        classes: Res<Assets<ShipClass>>,
+        mut ships: Query<ClassedShip>,
+        mut models: Query<&mut Handle<Scene>>,
+    ) {
+        for event in events.iter() {
+            let AssetEvent::Modified { handle } = event else {
+                continue;
+            };
+            let Some(class) = classes.get(handle) else {
+                continue;
+            };
+
+            for (name, ship_class, mut ship, mut collider, mut mass, mut damping, children) in
+                ships.iter_mut()
+            {
+                if ship_class != handle {
+                    continue;
+                }
+
+                println!("Refitting {} with updated ship class ⚓", name.get());
+                ship.apply_class(class);
+                *collider = class.collider();
+                *mass = class.mass();
+                *damping = class.damping();
+                for child in children.iter() {
+                    if let Ok(mut model) = models.get_mut(*child) {
+                        *model = class.scene.clone();
+                    }
+                }
+            }
+        }
+    }
+}
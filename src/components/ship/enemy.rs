@@ -1,45 +1,36 @@
-use super::{spawn_ship, ShipMarker};
+use super::{class::ShipClass, spawn_ship, ShipAssets, ShipMarker};
+use crate::components::faction::Factions;
 use bevy::prelude::*;
-use bevy_rapier3d::prelude::Collider;
 
 pub struct Systems;
 
 impl Systems {
-    pub fn spawn_ship(mut commands: Commands, asset_server: Res<AssetServer>) {
-        let ship_handle =
-            asset_server.load("models/pirate_ship/dutch_ship_large_01_1k.gltf#Scene0");
-        let ship_collider = Collider::cuboid(8.0, 15.0, 10.0);
-        spawn_ship(
-            ShipMarker::Enemy,
-            "S.S Bath Time",
-            Vec3::new(-50.0, 0.0, 0.0),
-            &mut commands,
-            &ship_handle,
-            ship_collider.clone(),
-        );
-        spawn_ship(
-            ShipMarker::Enemy,
-            "S.S Bath Time",
-            Vec3::new(50.0, 0.0, 0.0),
-            &mut commands,
-            &ship_handle,
-            ship_collider.clone(),
-        );
-        spawn_ship(
-            ShipMarker::Enemy,
-            "S.S Bath Time",
-            Vec3::new(-50.0, 0.0, -50.0),
-            &mut commands,
-            &ship_handle,
-            ship_collider.clone(),
-        );
-        spawn_ship(
-            ShipMarker::Enemy,
-            "S.S Bath Time",
-            Vec3::new(50.0, 0.0, 50.0),
-            &mut commands,
-            &ship_handle,
-            ship_collider,
-        );
+    /// Sends out the AI ships, registering any faction they sail for that isn't in the faction table yet.
+    pub fn spawn_ship(
+        mut commands: Commands,
+        ship_assets: Res<ShipAssets>,
+        classes: Res<Assets<ShipClass>>,
+        mut factions: ResMut<Factions>,
+    ) {
+        let ship_class = &ship_assets.dutch_ship;
+        for (faction, name, location) in [
+            ("Navy", "S.S Bath Time", Vec3::new(-50.0, 0.0, 0.0)),
+            ("Navy", "S.S Bath Time", Vec3::new(50.0, 0.0, 0.0)),
+            ("Pirates", "Salty Dog", Vec3::new(-50.0, 0.0, -50.0)),
+            ("Merchants", "Fair Trade", Vec3::new(50.0, 0.0, 50.0)),
+        ] {
+            let Some(faction) = factions.find_or_add(faction) else {
+                continue;
+            };
+            spawn_ship(
+                ShipMarker::Ai,
+                faction,
+                name,
+                location,
+                &mut commands,
+                ship_class,
+                &classes,
+            );
+        }
     }
 }
@@ -0,0 +1,304 @@
+use super::ai::{Orders, ShipAi};
+use super::sinking::Sinking;
+use super::{PlayerShip, Ship};
+use crate::components::{
+    controls::{Action, Actions},
+    faction::{Faction, Factions},
+    id::Name,
+};
+use bevy::{input::mouse::MouseMotion, prelude::*};
+use std::f32::consts::TAU;
+
+// Distance between neighbouring ships in formation
+const FORMATION_SPACING: f32 = 40.0;
+// How far the pointer can stray from the centre of the fleet menu, in pixels
+const MENU_POINTER_RANGE: f32 = 100.0;
+// How far the pointer needs to move before a command is picked, in pixels
+const MENU_DEADZONE: f32 = 30.0;
+
+type Flagship = (With<PlayerShip>, Without<Sinking>);
+type FleetShip<'a> = (Entity, &'a mut ShipAi, &'a Faction);
+type OtherShips = (Without<PlayerShip>, Without<Sinking>);
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Formation {
+    // Single file astern of the player
+    #[default]
+    LineAhead,
+    // Side by side with the player, alternating starboard and port
+    LineAbreast,
+    // Fanning out astern of the player on either side
+    Wedge,
+}
+
+impl Formation {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::LineAhead => "Line ahead",
+            Self::LineAbreast => "Line abreast",
+            Self::Wedge => "Wedge",
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            Self::LineAhead => Self::LineAbreast,
+            Self::LineAbreast => Self::Wedge,
+            Self::Wedge => Self::LineAhead,
+        }
+    }
+
+    /// Where the ship in `slot` keeps station, relative to the player's ship.
+    /// The player's bow points along -z and their right side along +x.
+    fn offset(&self, slot: usize) -> Vec3 {
+        let rank = (slot / 2 + 1) as f32 * FORMATION_SPACING;
+        let side = if slot.is_multiple_of(2) { 1.0 } else { -1.0 };
+        match self {
+            Self::LineAhead => Vec3::new(0.0, 0.0, (slot + 1) as f32 * FORMATION_SPACING),
+            Self::LineAbreast => Vec3::new(rank * side, 0.0, 0.0),
+            Self::Wedge => Vec3::new(rank * side, 0.0, rank),
+        }
+    }
+}
+
+/// Something the player can tell their fleet to do, from a hotkey or the fleet menu.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FleetCommand {
+    FormUp(Formation),
+    Attack,
+    Hold,
+    Retreat,
+}
+
+impl FleetCommand {
+    /// Commands around the fleet menu, clockwise from the top.
+    pub const MENU: [FleetCommand; 6] = [
+        FleetCommand::FormUp(Formation::LineAhead),
+        FleetCommand::FormUp(Formation::LineAbreast),
+        FleetCommand::FormUp(Formation::Wedge),
+        FleetCommand::Attack,
+        FleetCommand::Hold,
+        FleetCommand::Retreat,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::FormUp(formation) => formation.label(),
+            Self::Attack => "Attack",
+            Self::Hold => "Hold position",
+            Self::Retreat => "Retreat",
+        }
+    }
+
+    /// Screen direction of the command's place on the fleet menu, with y pointing down.
+    pub fn menu_direction(index: usize) -> Vec2 {
+        let angle = index as f32 * TAU / Self::MENU.len() as f32;
+        Vec2::new(angle.sin(), -angle.cos())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum FleetOrder {
+    #[default]
+    FormUp,
+    Attack(Entity),
+    Hold,
+    Retreat,
+}
+
+/// The player's standing orders to every AI ship of their faction.
+#[derive(Resource, Default)]
+pub struct Fleet {
+    formation: Formation,
+    order: FleetOrder,
+    // Ships under the player's command, not counting their own
+    size: usize,
+}
+
+impl Fleet {
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn label(&self) -> String {
+        match self.order {
+            FleetOrder::FormUp => format!("Forming {}", self.formation.label().to_lowercase()),
+            FleetOrder::Attack(_) => "Attacking".to_string(),
+            FleetOrder::Hold => "Holding position".to_string(),
+            FleetOrder::Retreat => "Retreating".to_string(),
+        }
+    }
+}
+
+/// Radial menu of fleet commands, open while the fleet menu action is held.
+/// The mouse or right stick picks a command, and letting go gives it.
+#[derive(Resource, Default)]
+pub struct FleetMenu {
+    open: bool,
+    pointer: Vec2,
+}
+
+impl FleetMenu {
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn selected(&self) -> Option<FleetCommand> {
+        if self.pointer.length() < MENU_DEADZONE {
+            return None;
+        }
+        let step = TAU / FleetCommand::MENU.len() as f32;
+        let angle = self.pointer.x.atan2(-self.pointer.y);
+        let index = (angle / step).round() as i32;
+        Some(FleetCommand::MENU[index.rem_euclid(FleetCommand::MENU.len() as i32) as usize])
+    }
+}
+
+pub struct Systems;
+impl Systems {
+    pub fn reset_fleet(mut commands: Commands) {
+        commands.insert_resource(Fleet::default());
+        commands.insert_resource(FleetMenu::default());
+    }
+
+    /// Takes commands from the hotkeys and the fleet menu. Attack orders go after the nearest ship hostile to the player.
+    pub fn command(
+        actions: Actions,
+        mut mouse: EventReader<MouseMotion>,
+        mut menu: ResMut<FleetMenu>,
+        mut fleet: ResMut<Fleet>,
+        player: Query<(&Transform, &Faction), Flagship>,
+        ships: Query<(Entity, &Transform, &Faction, &Name), OtherShips>,
+        factions: Res<Factions>,
+    ) {
+        let mut command = None;
+        if actions.just_pressed(Action::FormUp) {
+            // Asking a fleet that's already formed up to form up again moves it on to the next formation
+            let formation = if fleet.order == FleetOrder::FormUp {
+                fleet.formation.next()
+            } else {
+                fleet.formation
+            };
+            command = Some(FleetCommand::FormUp(formation));
+        } else if actions.just_pressed(Action::Attack) {
+            command = Some(FleetCommand::Attack);
+        } else if actions.just_pressed(Action::Hold) {
+            command = Some(FleetCommand::Hold);
+        } else if actions.just_pressed(Action::Retreat) {
+            command = Some(FleetCommand::Retreat);
+        }
+
+        if actions.just_pressed(Action::FleetMenu) {
+            menu.open = true;
+            menu.pointer = Vec2::ZERO;
+        }
+        if menu.open {
+            for motion in mouse.iter() {
+                menu.pointer += motion.delta;
+            }
+            let look = actions.look();
+            if look != Vec2::ZERO {
+                // Stick y points up where the screen's points down
+                menu.pointer = Vec2::new(look.x, -look.y) * MENU_POINTER_RANGE;
+            }
+            menu.pointer = menu.pointer.clamp_length_max(MENU_POINTER_RANGE);
+            if !actions.pressed(Action::FleetMenu) {
+                menu.open = false;
+                command = menu.selected();
+            }
+        }
+
+        let Some(command) = command else {
+            return;
+        };
+        if fleet.size == 0 {
+            println!("There are no ships under our command");
+            return;
+        }
+        fleet.order = match command {
+            FleetCommand::FormUp(formation) => {
+                fleet.formation = formation;
+                FleetOrder::FormUp
+            }
+            FleetCommand::Attack => {
+                let Ok((player_transform, player_faction)) = player.get_single() else {
+                    return;
+                };
+                let target = ships
+                    .iter()
+                    .filter(|(_, _, faction, _)| factions.is_hostile(*player_faction, **faction))
+                    .min_by(|(_, a, _, _), (_, b, _, _)| {
+                        let a = a.translation.distance(player_transform.translation);
+                        let b = b.translation.distance(player_transform.translation);
+                        a.total_cmp(&b)
+                    });
+                let Some((target, _, _, name)) = target else {
+                    println!("There's nothing to attack");
+                    return;
+                };
+                println!("Fleet, attack {}!", name.get());
+                FleetOrder::Attack(target)
+            }
+            FleetCommand::Hold => FleetOrder::Hold,
+            FleetCommand::Retreat => FleetOrder::Retreat,
+        };
+        println!("Fleet orders: {}", fleet.label());
+    }
+
+    /// Passes the fleet's orders on to each AI ship of the player's faction, giving each one its own station.
+    /// Once an attack order's target has gone down, or is no longer hostile, the fleet forms up again.
+    pub fn keep_formation(
+        mut fleet: ResMut<Fleet>,
+        player: Query<(&Transform, &Ship, &Faction), Flagship>,
+        mut ships: Query<FleetShip, OtherShips>,
+        targets: Query<&Faction, Without<Sinking>>,
+        factions: Res<Factions>,
+    ) {
+        let Ok((player_transform, player_ship, player_faction)) = player.get_single() else {
+            fleet.size = 0;
+            return;
+        };
+        if let FleetOrder::Attack(target) = fleet.order {
+            match targets.get(target) {
+                Err(_) => {
+                    fleet.order = FleetOrder::FormUp;
+                    println!("Target destroyed, fleet forming up");
+                }
+                Ok(faction) if !factions.is_hostile(*player_faction, *faction) => {
+                    fleet.order = FleetOrder::FormUp;
+                    println!("Target has struck her colours, fleet forming up");
+                }
+                Ok(_) => {}
+            }
+        }
+
+        // Ordered by entity, so each ship keeps the same station from frame to frame
+        let mut fleet_ships: Vec<(Entity, Mut<ShipAi>)> = ships
+            .iter_mut()
+            .filter(|(_, _, faction)| *faction == player_faction)
+            .map(|(entity, ai, _)| (entity, ai))
+            .collect();
+        fleet_ships.sort_by_key(|(entity, _)| *entity);
+        fleet.size = fleet_ships.len();
+
+        let forward = player_transform.forward();
+        let heading = Vec3::new(forward.x, 0.0, forward.z).normalize_or_zero();
+        for (slot, (_, mut ai)) in fleet_ships.into_iter().enumerate() {
+            let orders = match fleet.order {
+                FleetOrder::FormUp => {
+                    let offset = Quat::from_rotation_arc(Vec3::NEG_Z, heading)
+                        * fleet.formation.offset(slot);
+                    Orders::Station {
+                        position: player_transform.translation + offset,
+                        heading,
+                        sails: player_ship.sails().clone(),
+                    }
+                }
+                FleetOrder::Attack(target) => Orders::Attack(target),
+                FleetOrder::Hold => Orders::Hold,
+                FleetOrder::Retreat => Orders::Retreat,
+            };
+            ai.set_orders(Some(orders));
+        }
+    }
+}
@@ -1,98 +1,417 @@
-use super::{collider_group::AsCollisionGroups, health::Health, id::Name};
+use super::{
+    collider_group, faction::Faction, health::Health, id::Name, session::SessionEntity, wind::Wind,
+};
+use ammo::{AmmoStores, AmmoType};
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::{
-    AdditionalMassProperties, Collider, CollisionGroups, Damping, LockedAxes, RigidBody, Velocity,
+    ActiveEvents, AdditionalMassProperties, Collider, CollisionGroups, Damping, GravityScale,
+    RigidBody, Velocity,
 };
-use cannons::Cannons;
+use cannons::{Broadside, CannonDirection, Cannons};
+use class::ShipClass;
+use sections::Section;
+use serde::{Deserialize, Serialize};
+use std::{f32::consts::TAU, time::Duration};
 
-mod camera;
+pub mod ai;
+pub mod ammo;
+pub mod boarding;
+pub mod buoyancy;
+pub mod camera;
 pub mod cannons;
+pub mod class;
 pub mod enemy;
+pub mod fleet;
 pub mod player;
-mod sails;
+pub mod ramming;
+pub mod repair;
+pub mod sails;
+pub mod save;
+pub mod sections;
+pub mod sinking;
+
+// Base ship turn speed. Will be modified by the ship's velocity.
+const SHIP_TURN_SPEED: f32 = 0.05;
+// Masts needed to carry full sail, as a fraction of undamaged masts
+const FULL_SAIL_MASTS: f32 = 0.5;
+// Turning speed left to a ship whose rudder has been shot away
+const MIN_RUDDER_EFFICIENCY: f32 = 0.2;
+// However few hands are left, reloads take at most this many times longer than with a full crew
+const MIN_CREW_EFFICIENCY: f32 = 0.25;
+
+#[derive(Resource)]
+pub struct ShipAssets {
+    pub dutch_ship: Handle<ShipClass>,
+}
+
+impl ShipAssets {
+    pub fn load(asset_server: &AssetServer) -> Self {
+        Self {
+            dutch_ship: asset_server.load("ships/dutch_ship_large.ship.ron"),
+        }
+    }
+}
 
 #[derive(Bundle, Default)]
 pub struct ShipBundle {
+    session: SessionEntity,
     name: Name,
+    marker: ShipMarker,
+    faction: Faction,
+    class: Handle<ShipClass>,
     spatial_bundle: SpatialBundle,
     collider: Collider,
     ship: Ship,
     collision_group: CollisionGroups,
     rigidbody: RigidBody,
-    locked_axis: LockedAxes,
+    gravity: GravityScale,
     mass: AdditionalMassProperties,
     velocity: Velocity,
     damping: Damping,
+    active_events: ActiveEvents,
 }
 
 #[derive(Component, Default)]
 pub struct Ship {
     sails: sails::SailState,
+    sail_speeds: sails::SailSpeeds,
+    // The hull, the ship sinks once it's gone
     health: Health,
+    masts: Health,
+    rudder: Health,
+    left_battery: Health,
+    right_battery: Health,
+    crew: Health,
     cannons: Cannons,
+    ammo: AmmoStores,
+    // Timber, canvas and the like, used up one for each point of health repaired
+    supplies: f32,
+    repairing: bool,
 }
 
-#[derive(PartialEq, Clone, Component)]
-pub enum ShipMarker {
-    Player,
-    Enemy,
-}
+impl Ship {
+    fn new(class: &ShipClass) -> Self {
+        Self {
+            sails: sails::SailState::default(),
+            sail_speeds: class.sail_speeds.clone(),
+            health: Health::new(class.health),
+            masts: Health::new(class.masts),
+            rudder: Health::new(class.rudder),
+            left_battery: Health::new(class.battery),
+            right_battery: Health::new(class.battery),
+            crew: Health::new(class.crew),
+            cannons: Cannons::new(&class.cannons),
+            ammo: AmmoStores::new(&class.ammo),
+            supplies: class.repair_supplies,
+            repairing: false,
+        }
+    }
+
+    /// Updates the ship's stats to match its class, keeping any damage, reload progress and ammo.
+    pub fn apply_class(&mut self, class: &ShipClass) {
+        self.sail_speeds = class.sail_speeds.clone();
+        self.health.set_max(class.health);
+        self.masts.set_max(class.masts);
+        self.rudder.set_max(class.rudder);
+        self.left_battery.set_max(class.battery);
+        self.right_battery.set_max(class.battery);
+        self.crew.set_max(class.crew);
+        self.cannons.configure(&class.cannons);
+    }
+
+    pub fn sails(&self) -> &sails::SailState {
+        &self.sails
+    }
+
+    pub fn health(&self) -> &Health {
+        &self.health
+    }
+
+    pub fn section(&self, section: Section) -> &Health {
+        match section {
+            Section::Hull => &self.health,
+            Section::Masts => &self.masts,
+            Section::Rudder => &self.rudder,
+            Section::Battery(CannonDirection::Left) => &self.left_battery,
+            Section::Battery(CannonDirection::Right) => &self.right_battery,
+        }
+    }
+
+    fn section_mut(&mut self, section: Section) -> &mut Health {
+        match section {
+            Section::Hull => &mut self.health,
+            Section::Masts => &mut self.masts,
+            Section::Rudder => &mut self.rudder,
+            Section::Battery(CannonDirection::Left) => &mut self.left_battery,
+            Section::Battery(CannonDirection::Right) => &mut self.right_battery,
+        }
+    }
+
+    pub fn crew(&self) -> &Health {
+        &self.crew
+    }
+
+    pub fn cannons(&self) -> &Cannons {
+        &self.cannons
+    }
+
+    pub fn ammo(&self) -> &AmmoStores {
+        &self.ammo
+    }
+
+    /// Most sail the masts can still carry.
+    pub fn max_sails(&self) -> sails::SailState {
+        if self.masts.fraction() > FULL_SAIL_MASTS {
+            sails::SailState::Full
+        } else if !self.masts.is_dead() {
+            sails::SailState::Mid
+        } else {
+            sails::SailState::None
+        }
+    }
+
+    /// Sets the sails, as far as the masts allow. Making sail puts a stop to any repairs.
+    pub fn set_sails(&mut self, sails: sails::SailState) {
+        let max_sails = self.max_sails();
+        self.sails = if sails > max_sails { max_sails } else { sails };
+        if self.sails != sails::SailState::None {
+            self.stop_repairs();
+        }
+    }
+
+    pub fn supplies(&self) -> f32 {
+        self.supplies
+    }
 
-impl Default for ShipMarker {
-    fn default() -> Self {
-        Self::Player
+    pub fn is_repairing(&self) -> bool {
+        self.repairing
+    }
+
+    /// Sections that have been knocked out entirely are beyond repair at sea.
+    fn needs_repair(&self) -> bool {
+        Section::ALL.iter().any(|section| {
+            let health = self.section(*section);
+            !health.is_dead() && health.current() < health.max()
+        })
+    }
+
+    /// Repairs can only be made with the sails furled and supplies to spare.
+    pub fn start_repairs(&mut self) {
+        if self.sails != sails::SailState::None {
+            println!("Furl the sails before starting repairs");
+        } else if self.supplies <= 0.0 {
+            println!("We've no supplies left to make repairs with");
+        } else if !self.needs_repair() {
+            println!("Nothing needs repairing");
+        } else {
+            self.repairing = true;
+        }
+    }
+
+    pub fn stop_repairs(&mut self) {
+        self.repairing = false;
+    }
+
+    /// Heals each damaged section by up to `amount`, for as long as the supplies last.
+    fn repair(&mut self, amount: f32) {
+        let mut supplies = self.supplies;
+        for section in Section::ALL {
+            let health = self.section_mut(section);
+            if health.is_dead() {
+                continue;
+            }
+            let repaired = amount.min(health.max() - health.current()).min(supplies);
+            health.heal(repaired);
+            supplies -= repaired;
+        }
+        self.supplies = supplies;
+
+        if !self.needs_repair() {
+            println!("Repairs complete 🔨");
+            self.stop_repairs();
+        } else if self.supplies <= 0.0 {
+            println!("We've run out of supplies, repairs will have to wait");
+            self.stop_repairs();
+        }
+    }
+
+    /// Health across every section, from 0.0 (wrecked) to 1.0 (good as new).
+    pub fn condition(&self) -> f32 {
+        let (current, max) = Section::ALL
+            .iter()
+            .fold((0.0, 0.0), |(current, max), section| {
+                let health = self.section(*section);
+                (current + health.current(), max + health.max())
+            });
+        current / max
+    }
+
+    /// Damages the section that was hit, along with whatever the ammo is made to tear through wherever it lands.
+    pub fn take_hit(&mut self, ammo: AmmoType, section: Section) {
+        let damage = ammo.damage();
+        self.section_mut(section).take_damage(damage.section);
+        self.masts.take_damage(damage.masts);
+        self.crew.take_damage(damage.crew);
+        // Damaged masts can't hold up the sails they had
+        self.set_sails(self.sails.clone());
+        self.interrupt_repairs("incoming fire");
+    }
+
+    /// Damages the hull after a collision with another ship.
+    pub fn take_collision(&mut self, damage: f32) {
+        self.health.take_damage(damage);
+        self.interrupt_repairs("the collision");
+    }
+
+    fn interrupt_repairs(&mut self, cause: &str) {
+        if self.repairing {
+            println!("Repairs interrupted by {}!", cause);
+            self.stop_repairs();
+        }
+    }
+
+    /// Fires a broadside of the selected ammo, if this side is loaded and there's ammo left.
+    /// `collision_groups` are the ship's faction's, see `collider_group::cannon_collision_groups`.
+    pub fn fire(
+        &mut self,
+        commands: &mut Commands,
+        time_elapsed: Duration,
+        transform: &Transform,
+        velocity: &Velocity,
+        direction: CannonDirection,
+        collision_groups: CollisionGroups,
+    ) {
+        let battery = Section::Battery(direction);
+        if self.section(battery).is_dead() {
+            println!("The {} is out of action!", battery.label());
+            return;
+        }
+        if self.crew.is_dead() {
+            println!("There's no one left to man the guns!");
+            return;
+        }
+        if !self.cannons.is_loaded(time_elapsed, direction) {
+            println!("Reloading these cannons! 🏴‍☠️");
+            return;
+        }
+        let ammo = self.ammo.selected();
+        let balls = self.ammo.take(self.cannons.count());
+        if balls == 0 {
+            println!("We're out of {}!", ammo.label().to_lowercase());
+            return;
+        }
+        // Short handed gun crews take longer to reload
+        let crew_efficiency = self.crew.fraction().max(MIN_CREW_EFFICIENCY);
+        let reload_time = self
+            .cannons
+            .reload_time()
+            .mul_f32(ammo.reload_multiplier() / crew_efficiency);
+        self.cannons.fire(
+            commands,
+            time_elapsed,
+            transform,
+            velocity.linvel,
+            direction,
+            Broadside {
+                ammo,
+                balls,
+                reload_time,
+                collision_groups,
+            },
+        );
+    }
+
+    /// Rotates the ship, where `helm` ranges from -1.0 (hard to the right) to 1.0 (hard to the left).
+    /// A ship with no crew left has no one at the helm.
+    pub fn turn(&self, transform: &mut Transform, helm: f32, wind: &Wind, delta_seconds: f32) {
+        if self.crew.is_dead() {
+            return;
+        }
+        let wind_factor = wind.speed_factor(transform.forward());
+        transform.rotate_y(
+            SHIP_TURN_SPEED
+                * helm.clamp(-1.0, 1.0)
+                * self.sails.as_rotation_speed(wind_factor)
+                * self.rudder.fraction().max(MIN_RUDDER_EFFICIENCY)
+                * TAU
+                * delta_seconds,
+        );
+    }
+
+    /// A ship with no crew left to work the sails drifts to a stop.
+    pub fn velocity(&self, transform: &Transform, wind: &Wind) -> Velocity {
+        if self.crew.is_dead() {
+            return Velocity::zero();
+        }
+        let wind_factor = wind.speed_factor(transform.forward());
+        Velocity {
+            linvel: self.sails.as_forward_speed(
+                transform.forward(),
+                &self.sail_speeds,
+                wind_factor,
+            ),
+            angvel: Vec3::ZERO,
+        }
     }
 }
 
-#[derive(Component)]
-pub struct PlayerShip;
+/// Who is at the helm. Which side the ship is on is up to its `Faction`.
+#[derive(PartialEq, Clone, Component, Default, Serialize, Deserialize)]
+pub enum ShipMarker {
+    #[default]
+    Player,
+    Ai,
+}
 
 #[derive(Component)]
-pub struct EnemyShip;
+pub struct PlayerShip;
 
 pub fn spawn_ship(
     marker: ShipMarker,
-    name: &'static str,
+    faction: Faction,
+    name: &str,
     location: Vec3,
     commands: &mut Commands,
-    ship_handle: &Handle<Scene>,
-    ship_collider: Collider,
-) {
+    class_handle: &Handle<ShipClass>,
+    classes: &Assets<ShipClass>,
+) -> Option<Entity> {
+    let Some(class) = classes.get(class_handle) else {
+        println!("Tried to spawn {} before its ship class was loaded", name);
+        return None;
+    };
+
     let mut ship = commands.spawn(ShipBundle {
         name: Name::new(name),
+        marker: marker.clone(),
+        faction,
+        class: class_handle.clone(),
         spatial_bundle: SpatialBundle::from_transform(Transform::from_translation(location)),
-        collider: ship_collider,
-        collision_group: marker.as_collision_groups(),
+        collider: class.collider(),
+        ship: Ship::new(class),
+        collision_group: collider_group::ship_collision_groups(faction),
         rigidbody: RigidBody::Dynamic,
-        locked_axis: LockedAxes::TRANSLATION_LOCKED_Y
-            | LockedAxes::ROTATION_LOCKED_X
-            | LockedAxes::ROTATION_LOCKED_Z,
-        mass: AdditionalMassProperties::Mass(2000.0),
-        damping: Damping {
-            linear_damping: 100.0,
-            angular_damping: 20.0,
-        },
+        // Buoyancy keeps ships afloat, rather than fighting gravity every frame
+        gravity: GravityScale(0.0),
+        mass: class.mass(),
+        damping: class.damping(),
+        // Required for Rapier to report ships running into each other
+        active_events: ActiveEvents::COLLISION_EVENTS,
         ..default()
     });
 
     match marker {
         ShipMarker::Player => ship.insert(PlayerShip),
-        ShipMarker::Enemy => ship.insert(EnemyShip),
+        ShipMarker::Ai => ship.insert(ai::ShipAi::new(location)),
     };
 
     ship.with_children(|parent| {
         parent.spawn(SceneBundle {
-            scene: ship_handle.clone(),
+            scene: class.scene.clone(),
             // Rotate ship model to line up with rotation axis.
             transform: Transform::from_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2)),
             ..Default::default()
         });
     });
 
-    if marker == ShipMarker::Player {
-        ship.with_children(|parent| {
-            // Spawn ship camera
-            parent.spawn(camera::ShipCameraBundle::default());
-        });
-    }
+    Some(ship.id())
 }
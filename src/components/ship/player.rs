@@ -1,129 +1,260 @@
-use std::f32::consts::TAU;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{RapierConfiguration, Velocity};
+use bevy_water::WaterParam;
 
-use bevy::{input::mouse::MouseMotion, prelude::*};
-use bevy_rapier3d::prelude::{Collider, Velocity};
+use super::camera::{CameraInput, CameraMode, OrbitCamera, ShipCamera, CAMERA_FOV};
+use super::cannons::{self, CannonDirection};
+use super::class::ShipClass;
+use super::sinking::Sinking;
+use super::{spawn_ship, PlayerShip, Ship, ShipAssets, ShipMarker};
+use crate::components::collider_group;
+use crate::components::controls::{Action, Actions};
+use crate::components::faction::{Faction, Factions};
+use crate::components::wind::Wind;
 
-use super::camera::ShipCamera;
-use super::cannons::CannonDirection;
-use super::{spawn_ship, PlayerShip, Ship, ShipMarker};
+// How quickly the camera swings over to the aiming view and back
+const AIM_CAMERA_SPEED: f32 = 6.0;
+// Where the camera sits while aiming, above the deck on the side away from the guns
+const AIM_CAMERA_HEIGHT: f32 = 16.0;
+const AIM_CAMERA_INBOARD: f32 = 10.0;
+// Field of view while aiming, in radians
+const AIM_FOV: f32 = 0.35;
 
-const SHIP_SPEED: f32 = 20.0;
-// Base ship turn speed. Will be modified by the ship's velocity.
-const SHIP_TURN_SPEED: (f32, f32) = (-0.05, 0.05);
-const CAMERA_SPEED: f32 = 1.0;
-const CAMERA_MAX_HEIGHT: f32 = 40.0;
-// Max distance camera can pan left and right
-const CAMERA_MAX_PAN: (f32, f32) = (-40.0, 40.0);
+// How quickly cannons can be raised or lowered, in radians per second
+const ELEVATION_SPEED: f32 = 0.25;
+
+// The player loses control of their ship once it starts sinking
+type ControllableShip = (With<PlayerShip>, Without<Sinking>);
+type ChaseCamera = (With<ShipCamera>, Without<PlayerShip>);
+type ChaseCameraView<'a> = (&'a mut Transform, &'a mut Projection, &'a mut OrbitCamera);
+
+/// The broadside the player is aiming, if any, and where it would land.
+#[derive(Resource, Default)]
+pub struct Aim {
+    side: Option<CannonDirection>,
+    landing: Vec3,
+    // Distance across the water from the gun ports to where the balls land
+    range: f32,
+    elevation: f32,
+}
+
+impl Aim {
+    pub fn side(&self) -> Option<CannonDirection> {
+        self.side
+    }
+
+    pub fn range(&self) -> f32 {
+        self.range
+    }
+
+    /// Radians above the horizon.
+    pub fn elevation(&self) -> f32 {
+        self.elevation
+    }
+}
 
 pub struct Systems;
 impl Systems {
-    pub fn spawn_ship(mut commands: Commands, asset_server: Res<AssetServer>) {
-        let ship_handle: Handle<Scene> =
-            asset_server.load("models/pirate_ship/dutch_ship_large_01_1k.gltf#Scene0");
-        let ship_collider = Collider::cuboid(8.0, 15.0, 10.0);
+    pub fn spawn_ship(
+        mut commands: Commands,
+        ship_assets: Res<ShipAssets>,
+        classes: Res<Assets<ShipClass>>,
+    ) {
         spawn_ship(
             ShipMarker::Player,
+            Faction::PLAYER,
             "Eleanor",
             Vec3::ZERO,
             &mut commands,
-            &ship_handle,
-            ship_collider,
+            &ship_assets.dutch_ship,
+            &classes,
         );
     }
 
+    /// While spectating, the sailing controls fly the camera instead, and the ship holds her course.
     pub fn movement(
-        mut ship: Query<(&mut Transform, &mut Velocity, &mut Ship), With<PlayerShip>>,
-        keyboard: Res<Input<KeyCode>>,
+        mut ship: Query<(&mut Transform, &mut Velocity, &mut Ship), ControllableShip>,
+        actions: Actions,
+        mode: Res<CameraMode>,
+        wind: Res<Wind>,
         time: Res<Time>,
     ) {
-        let (mut ship, mut ship_velocity, mut ship_state) = ship.single_mut();
+        let Ok((mut ship, mut ship_velocity, mut ship_state)) = ship.get_single_mut() else {
+            return;
+        };
+        // The sails, helm and repairs are left as they were while spectating
+        if mode.is_spectating() {
+            *ship_velocity = ship_state.velocity(&ship, &wind);
+            return;
+        }
 
         // Change sails position
-        if keyboard.just_pressed(KeyCode::W) {
-            ship_state.sails = ship_state.sails.speed_up();
-        } else if keyboard.just_pressed(KeyCode::S) {
-            ship_state.sails = ship_state.sails.slow_down();
+        if actions.just_pressed(Action::LowerSails) {
+            let sails = ship_state.sails.speed_up();
+            ship_state.set_sails(sails);
+        } else if actions.just_pressed(Action::RaiseSails) {
+            let sails = ship_state.sails.slow_down();
+            ship_state.set_sails(sails);
         }
 
-        if keyboard.pressed(KeyCode::D) {
-            ship.rotate_y(
-                SHIP_TURN_SPEED.0
-                    * ship_state.sails.as_rotation_speed()
-                    * TAU
-                    * time.delta_seconds(),
-            );
-        }
-        if keyboard.pressed(KeyCode::A) {
-            ship.rotate_y(
-                SHIP_TURN_SPEED.1
-                    * ship_state.sails.as_rotation_speed()
-                    * TAU
-                    * time.delta_seconds(),
-            );
+        if actions.just_pressed(Action::Repair) {
+            if ship_state.is_repairing() {
+                ship_state.stop_repairs();
+            } else {
+                ship_state.start_repairs();
+            }
         }
 
-        let ship_offset = SHIP_SPEED * ship_state.sails.as_forward_speed(ship.forward());
+        ship_state.turn(&mut ship, actions.helm(), &wind, time.delta_seconds());
 
-        *ship_velocity = Velocity {
-            linvel: ship_offset,
-            angvel: Vec3::ZERO,
-        };
+        *ship_velocity = ship_state.velocity(&ship, &wind);
     }
 
+    /// Orbits the camera around the player's ship. The mouse or right stick swing it around and the scroll wheel
+    /// zooms, with the camera easing after the input and kept clear of the waves.
+    /// While a broadside is being aimed, the camera swings over the deck to look out at where it will land.
     pub fn camera(
-        mut camera: Query<&mut Transform, With<ShipCamera>>,
-        mut mouse: EventReader<MouseMotion>,
+        mut camera: Query<ChaseCameraView, ChaseCamera>,
+        ship: Query<&Transform, With<PlayerShip>>,
+        mut input: CameraInput,
+        aim: Res<Aim>,
+        mode: Res<CameraMode>,
+        water: WaterParam,
         time: Res<Time>,
     ) {
-        let mut camera = camera.single_mut();
-        // Adjust camera for mouse position
-        let mut camera_input_offset = Vec3::ZERO;
-        for mouse in mouse.iter() {
-            camera_input_offset =
-                Vec3::new(mouse.delta.x, mouse.delta.y, 0.0) * CAMERA_SPEED * time.delta_seconds();
+        if *mode != CameraMode::Chase {
+            return;
         }
+        let (Ok((mut camera, mut projection, mut orbit)), Ok(ship)) =
+            (camera.get_single_mut(), ship.get_single())
+        else {
+            return;
+        };
 
-        // Update camera position based on ship position and mouse inputs
-        camera.translation += camera_input_offset;
-
-        // Apply restrictions to ensure the camera is within its bounds, relative to player position
-        camera.translation = Vec3::new(
-            camera
-                .translation
-                .x
-                .clamp(CAMERA_MAX_PAN.0, CAMERA_MAX_PAN.1),
-            // NOTE: Not relative to player position. This shouldn't cause issues, unless the waves are huge.
-            camera.translation.y.clamp(6.0, CAMERA_MAX_HEIGHT),
-            camera.translation.z,
-        );
+        let swing = input.swing(time.delta_seconds());
+        let scroll = input.scroll();
+        // The orbit is left where it was while aiming, to come back to afterwards
+        if aim.side.is_none() {
+            orbit.steer(swing, scroll, time.delta_seconds());
+        } else {
+            orbit.steer(Vec2::ZERO, 0.0, time.delta_seconds());
+        }
+        let mut view = orbit.view(ship, &water);
 
-        // Have the camera look at a point relative to the parent (ship) component
-        camera.look_at(Vec3::new(0.0, 10.0, 0.0), Vec3::Y);
+        if let Some(side) = aim.side {
+            orbit.aim = Some((side, aim.landing));
+        }
+        let aiming = if aim.side.is_some() { 1.0 } else { 0.0 };
+        orbit.aiming +=
+            (aiming - orbit.aiming) * (1.0 - (-AIM_CAMERA_SPEED * time.delta_seconds()).exp());
+        if let Some((side, landing)) = orbit.aim {
+            let broadside = Vec3::new(ship.local_x().x, 0.0, ship.local_x().z).normalize_or_zero()
+                * side.as_linvel();
+            let aim_position =
+                ship.translation + Vec3::Y * AIM_CAMERA_HEIGHT - broadside * AIM_CAMERA_INBOARD;
+            let aim_view = Transform::from_translation(aim_position).looking_at(landing, Vec3::Y);
+            view.translation = view.translation.lerp(aim_view.translation, orbit.aiming);
+            view.rotation = view.rotation.slerp(aim_view.rotation, orbit.aiming);
+        }
+        if let Projection::Perspective(perspective) = projection.as_mut() {
+            perspective.fov = CAMERA_FOV + (AIM_FOV - CAMERA_FOV) * orbit.aiming;
+        }
+        *camera = view;
     }
 
     pub fn cannons(
         mut commands: Commands,
-        mut ship: Query<(&mut Ship, &Transform), With<PlayerShip>>,
-        keyboard: Res<Input<KeyCode>>,
+        mut ship: Query<(&mut Ship, &Transform, &Velocity, &Faction), ControllableShip>,
+        actions: Actions,
+        factions: Res<Factions>,
         time: Res<Time>,
     ) {
-        let (mut ship, ship_transform) = ship.single_mut();
-        if keyboard.just_pressed(KeyCode::Q) {
-            ship.cannons.fire(
+        let Ok((mut ship, ship_transform, ship_velocity, faction)) = ship.get_single_mut() else {
+            return;
+        };
+        let collision_groups = collider_group::cannon_collision_groups(*faction, &factions);
+        if actions.just_pressed(Action::NextAmmo) {
+            ship.ammo.select_next();
+        }
+
+        if actions.just_pressed(Action::FirePort) {
+            ship.fire(
                 &mut commands,
                 time.elapsed(),
                 ship_transform,
+                ship_velocity,
                 CannonDirection::Left,
+                collision_groups,
             );
         }
 
-        if keyboard.just_pressed(KeyCode::E) {
-            ship.cannons.fire(
+        if actions.just_pressed(Action::FireStarboard) {
+            ship.fire(
                 &mut commands,
                 time.elapsed(),
                 ship_transform,
+                ship_velocity,
                 CannonDirection::Right,
+                collision_groups,
+            );
+        }
+    }
+
+    /// While a side is being aimed, its elevation can be adjusted and the path its broadside will take is drawn.
+    pub fn aim(
+        mut aim: ResMut<Aim>,
+        mut ship: Query<(&mut Ship, &Transform, &Velocity), ControllableShip>,
+        actions: Actions,
+        water: WaterParam,
+        rapier: Res<RapierConfiguration>,
+        mut gizmos: Gizmos,
+        time: Res<Time>,
+    ) {
+        aim.side = None;
+        let Ok((mut ship, ship_transform, ship_velocity)) = ship.get_single_mut() else {
+            return;
+        };
+        let mut adjustment = 0.0;
+        if actions.pressed(Action::ElevateCannons) {
+            adjustment += ELEVATION_SPEED * time.delta_seconds();
+        }
+        if actions.pressed(Action::DepressCannons) {
+            adjustment -= ELEVATION_SPEED * time.delta_seconds();
+        }
+
+        let gravity = cannons::cannon_gravity(&rapier);
+        for (action, direction) in [
+            (Action::AimPort, CannonDirection::Left),
+            (Action::AimStarboard, CannonDirection::Right),
+        ] {
+            if !actions.pressed(action) {
+                continue;
+            }
+            let elevation = ship.cannons.elevation(direction);
+            ship.cannons
+                .set_elevation(direction, elevation + adjustment);
+
+            // Balls carry the ship's velocity, so the path is drawn with it too
+            let launch = ship.cannons.launch_position(ship_transform, direction);
+            let path = cannons::trajectory(
+                launch,
+                ship.cannons.launch_velocity(ship_transform, direction) + ship_velocity.linvel,
+                gravity,
+                &water,
             );
+            let color = if ship.cannons.is_loaded(time.elapsed(), direction) {
+                Color::WHITE
+            } else {
+                Color::GRAY
+            };
+            if let Some(landing) = path.last() {
+                gizmos.circle(*landing, Vec3::Y, 4.0, color);
+                aim.side = Some(direction);
+                aim.landing = *landing;
+                aim.range = Vec2::new(landing.x - launch.x, landing.z - launch.z).length();
+                aim.elevation = ship.cannons.elevation(direction);
+            }
+            gizmos.linestrip(path, color);
         }
     }
 }
@@ -0,0 +1,64 @@
+use super::{sinking::Sinking, Ship};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::f32::consts::FRAC_PI_8;
+
+// Closing speed below which ships just bump into each other
+const MIN_RAM_SPEED: f32 = 5.0;
+// Hull damage for each unit of closing speed
+const RAM_DAMAGE: f32 = 1.5;
+// How far either side of dead ahead still counts as striking with the bow, in radians
+const BOW_ARC: f32 = FRAC_PI_8;
+// Share of the damage a reinforced bow takes when ramming head on
+const BOW_REINFORCEMENT: f32 = 0.25;
+// Share of the damage taken from a blow to the bow or stern, rather than square on the side
+const GLANCING_BLOW: f32 = 0.3;
+
+type RammingShip<'a> = (&'a mut Ship, &'a Transform, &'a Velocity);
+
+/// How much of a collision's damage a ship takes, given the direction of the ship it collided with.
+/// A blow square on the side does the most damage, and a ship striking with its bow takes the least.
+fn damage_share(transform: &Transform, toward_other: Vec3) -> f32 {
+    let broadside = transform.local_x().dot(toward_other).abs();
+    let share = GLANCING_BLOW + (1.0 - GLANCING_BLOW) * broadside;
+    if transform.forward().angle_between(toward_other) < BOW_ARC {
+        share * BOW_REINFORCEMENT
+    } else {
+        share
+    }
+}
+
+pub struct Systems;
+impl Systems {
+    /// Damages both ships when they run into each other, by how fast they closed and the angle they met at.
+    pub fn ram_detection(
+        mut collisions: EventReader<CollisionEvent>,
+        mut ships: Query<RammingShip, Without<Sinking>>,
+    ) {
+        for collision in collisions.iter() {
+            let CollisionEvent::Started(a, b, _) = collision else {
+                continue;
+            };
+            let Ok([(mut ship_a, transform_a, velocity_a), (mut ship_b, transform_b, velocity_b)]) =
+                ships.get_many_mut([*a, *b])
+            else {
+                continue;
+            };
+
+            let a_to_b = Vec3::new(
+                transform_b.translation.x - transform_a.translation.x,
+                0.0,
+                transform_b.translation.z - transform_a.translation.z,
+            )
+            .normalize_or_zero();
+            let closing_speed = (velocity_a.linvel - velocity_b.linvel).dot(a_to_b);
+            if closing_speed < MIN_RAM_SPEED {
+                continue;
+            }
+
+            let damage = closing_speed * RAM_DAMAGE;
+            ship_a.take_collision(damage * damage_share(transform_a, a_to_b));
+            ship_b.take_collision(damage * damage_share(transform_b, -a_to_b));
+        }
+    }
+}
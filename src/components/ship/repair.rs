@@ -0,0 +1,16 @@
+use super::{sinking::Sinking, Ship};
+use bevy::prelude::*;
+
+// Health restored to each damaged section every second while repairing
+const REPAIR_RATE: f32 = 4.0;
+
+pub struct Systems;
+impl Systems {
+    pub fn repair(mut ships: Query<&mut Ship, Without<Sinking>>, time: Res<Time>) {
+        for mut ship in ships.iter_mut() {
+            if ship.repairing {
+                ship.repair(REPAIR_RATE * time.delta_seconds());
+            }
+        }
+    }
+}
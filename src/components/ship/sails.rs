@@ -1,18 +1,30 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(PartialEq, PartialOrd, Debug)]
-pub enum SailState {
-    None,
-    Mid,
-    Full,
+/// Top speed of a ship class at each sail setting, in a steady wind, see `ShipClass`.
+#[derive(Deserialize, Clone)]
+pub struct SailSpeeds {
+    pub mid: f32,
+    pub full: f32,
 }
 
-impl Default for SailState {
+impl Default for SailSpeeds {
     fn default() -> Self {
-        SailState::None
+        Self {
+            mid: 20.0,
+            full: 30.0,
+        }
     }
 }
 
+#[derive(PartialEq, PartialOrd, Debug, Default, Clone, Serialize, Deserialize)]
+pub enum SailState {
+    #[default]
+    None,
+    Mid,
+    Full,
+}
+
 impl SailState {
     pub fn speed_up(&self) -> Self {
         match &self {
@@ -30,19 +42,34 @@ impl SailState {
         }
     }
 
-    pub fn as_forward_speed(&self, ship_forward: Vec3) -> Vec3 {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::None => "Furled",
+            Self::Mid => "Half",
+            Self::Full => "Full",
+        }
+    }
+
+    /// `wind_factor` is how well the ship can use the wind on its current heading, see `Wind::speed_factor`.
+    pub fn as_forward_speed(
+        &self,
+        ship_forward: Vec3,
+        speeds: &SailSpeeds,
+        wind_factor: f32,
+    ) -> Vec3 {
         match self {
             Self::None => Vec3::ZERO,
-            Self::Mid => ship_forward * 1.0,
-            Self::Full => ship_forward * 1.5,
+            Self::Mid => ship_forward * speeds.mid * wind_factor,
+            Self::Full => ship_forward * speeds.full * wind_factor,
         }
     }
 
-    pub fn as_rotation_speed(&self) -> f32 {
+    pub fn as_rotation_speed(&self, wind_factor: f32) -> f32 {
+        // Ships turn better the faster they're moving, but can always be brought around slowly
         match self {
             Self::None => 0.125,
-            Self::Mid => 0.5,
-            Self::Full => 1.0,
+            Self::Mid => (0.5 * wind_factor).max(0.125),
+            Self::Full => (1.0 * wind_factor).max(0.125),
         }
     }
 }
@@ -0,0 +1,298 @@
+use super::{
+    ammo::AmmoStores, cannons::CannonDirection, class::ShipClass, sails::SailState,
+    sinking::Sinking, spawn_ship, Ship, ShipMarker,
+};
+use crate::{
+    components::{faction::Faction, id::Name},
+    MenuState,
+};
+use bevy::{asset::LoadState, prelude::*};
+use bevy_rapier3d::prelude::Velocity;
+use bevy_water::WaterSettings;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path, time::Duration};
+
+const SAVE_DIRECTORY: &str = "saves";
+const SAVE_FILE: &str = "saves/open_ocean.ron";
+// Bump whenever `SaveGame` changes shape, older saves are refused rather than misread
+const SAVE_VERSION: u32 = 5;
+
+#[derive(Event)]
+pub enum SaveRequest {
+    Save,
+    Load,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SaveGame {
+    version: u32,
+    water: WaterSave,
+    ships: Vec<ShipSave>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WaterSave {
+    height: f32,
+    amplitude: f32,
+    clarity: f32,
+    base_color: [f32; 4],
+    deep_color: [f32; 4],
+}
+
+#[derive(Serialize, Deserialize)]
+struct ShipSave {
+    name: String,
+    marker: ShipMarker,
+    faction: Faction,
+    // Asset path of the ship's class
+    class: String,
+    translation: [f32; 3],
+    rotation: [f32; 4],
+    linvel: [f32; 3],
+    angvel: [f32; 3],
+    sails: SailState,
+    // Health of the hull and each other section
+    health: f32,
+    masts: f32,
+    rudder: f32,
+    left_battery: f32,
+    right_battery: f32,
+    crew: f32,
+    ammo: AmmoStores,
+    supplies: f32,
+    // Seconds each side has spent reloading, out of how long its reload takes
+    left_cannons_reloading_for: f32,
+    left_cannons_reload_time: f32,
+    right_cannons_reloading_for: f32,
+    right_cannons_reload_time: f32,
+}
+
+/// A save that has been read from disk, waiting on its ship classes to load before it can be spawned.
+#[derive(Resource)]
+pub struct LoadedSave {
+    ships: Vec<(Handle<ShipClass>, ShipSave)>,
+}
+
+impl LoadedSave {
+    /// Load state of every ship class in the save, and the models they point to.
+    pub fn load_state(&self, asset_server: &AssetServer, classes: &Assets<ShipClass>) -> LoadState {
+        let mut handles: Vec<HandleUntyped> = Vec::new();
+        for (class, _) in self.ships.iter() {
+            match classes.get(class) {
+                Some(loaded) => handles.push(loaded.scene.clone_untyped()),
+                None => handles.push(class.clone_untyped()),
+            }
+        }
+        asset_server.get_group_load_state(handles.iter().map(|handle| handle.id()))
+    }
+}
+
+type SavedShip<'a> = (
+    &'a Name,
+    &'a ShipMarker,
+    &'a Faction,
+    &'a Handle<ShipClass>,
+    &'a Transform,
+    &'a Velocity,
+    &'a Ship,
+);
+
+pub struct Systems;
+impl Systems {
+    pub fn save_game(
+        mut requests: EventReader<SaveRequest>,
+        ships: Query<SavedShip, Without<Sinking>>,
+        water: Res<WaterSettings>,
+        asset_server: Res<AssetServer>,
+        time: Res<Time>,
+    ) {
+        if !requests
+            .iter()
+            .any(|request| matches!(request, SaveRequest::Save))
+        {
+            return;
+        }
+
+        let mut save = SaveGame {
+            version: SAVE_VERSION,
+            water: WaterSave {
+                height: water.height,
+                amplitude: water.amplitude,
+                clarity: water.clarity,
+                base_color: water.base_color.as_rgba_f32(),
+                deep_color: water.deep_color.as_rgba_f32(),
+            },
+            ships: Vec::new(),
+        };
+        for (name, marker, faction, class, transform, velocity, ship) in ships.iter() {
+            let Some(class_path) = asset_server.get_handle_path(class) else {
+                continue;
+            };
+            save.ships.push(ShipSave {
+                name: name.get().to_string(),
+                marker: marker.clone(),
+                faction: *faction,
+                class: class_path.path().to_string_lossy().to_string(),
+                translation: transform.translation.to_array(),
+                rotation: transform.rotation.to_array(),
+                linvel: velocity.linvel.to_array(),
+                angvel: velocity.angvel.to_array(),
+                sails: ship.sails.clone(),
+                health: ship.health.current(),
+                masts: ship.masts.current(),
+                rudder: ship.rudder.current(),
+                left_battery: ship.left_battery.current(),
+                right_battery: ship.right_battery.current(),
+                crew: ship.crew.current(),
+                ammo: ship.ammo.clone(),
+                supplies: ship.supplies,
+                left_cannons_reloading_for: ship
+                    .cannons
+                    .reloading_for(time.elapsed(), CannonDirection::Left)
+                    .as_secs_f32(),
+                left_cannons_reload_time: ship
+                    .cannons
+                    .side_reload_time(CannonDirection::Left)
+                    .as_secs_f32(),
+                right_cannons_reloading_for: ship
+                    .cannons
+                    .reloading_for(time.elapsed(), CannonDirection::Right)
+                    .as_secs_f32(),
+                right_cannons_reload_time: ship
+                    .cannons
+                    .side_reload_time(CannonDirection::Right)
+                    .as_secs_f32(),
+            });
+        }
+
+        let written = ron::ser::to_string_pretty(&save, ron::ser::PrettyConfig::default())
+            .map_err(|error| error.to_string())
+            .and_then(|contents| {
+                fs::create_dir_all(SAVE_DIRECTORY)
+                    .and_then(|_| fs::write(SAVE_FILE, contents))
+                    .map_err(|error| error.to_string())
+            });
+        match written {
+            Ok(_) => println!("Logged our voyage in the captain's log 📜"),
+            Err(error) => println!("Failed to save the game: {}", error),
+        }
+    }
+
+    /// Reads the save from disk and sets sail again once its ship classes have loaded.
+    pub fn load_game(
+        mut commands: Commands,
+        mut requests: EventReader<SaveRequest>,
+        asset_server: Res<AssetServer>,
+        mut next_game_state: ResMut<NextState<MenuState>>,
+    ) {
+        if !requests
+            .iter()
+            .any(|request| matches!(request, SaveRequest::Load))
+        {
+            return;
+        }
+        if !Path::new(SAVE_FILE).exists() {
+            println!("There's no saved game to load");
+            return;
+        }
+
+        let save: SaveGame = match fs::read_to_string(SAVE_FILE)
+            .map_err(|error| error.to_string())
+            .and_then(|contents| ron::from_str(&contents).map_err(|error| error.to_string()))
+        {
+            Ok(save) => save,
+            Err(error) => {
+                println!("Failed to load the saved game: {}", error);
+                return;
+            }
+        };
+        if save.version != SAVE_VERSION {
+            println!(
+                "Saved game is version {}, but only version {} can be loaded",
+                save.version, SAVE_VERSION
+            );
+            return;
+        }
+
+        commands.insert_resource(WaterSettings {
+            height: save.water.height,
+            amplitude: save.water.amplitude,
+            clarity: save.water.clarity,
+            base_color: save.water.base_color.into(),
+            deep_color: save.water.deep_color.into(),
+            ..default()
+        });
+        commands.insert_resource(LoadedSave {
+            ships: save
+                .ships
+                .into_iter()
+                .map(|ship| (asset_server.load(ship.class.as_str()), ship))
+                .collect(),
+        });
+        next_game_state.set(MenuState::Loading);
+    }
+
+    /// Rebuilds every saved ship, in place of the usual starting fleet.
+    pub fn restore_game(
+        mut commands: Commands,
+        save: Res<LoadedSave>,
+        classes: Res<Assets<ShipClass>>,
+        time: Res<Time>,
+    ) {
+        for (class, saved) in save.ships.iter() {
+            let Some(entity) = spawn_ship(
+                saved.marker.clone(),
+                saved.faction,
+                &saved.name,
+                Vec3::from_array(saved.translation),
+                &mut commands,
+                class,
+                &classes,
+            ) else {
+                continue;
+            };
+
+            let Some(class) = classes.get(class) else {
+                continue;
+            };
+            let mut ship = Ship::new(class);
+            ship.health.set_current(saved.health);
+            ship.masts.set_current(saved.masts);
+            ship.rudder.set_current(saved.rudder);
+            ship.left_battery.set_current(saved.left_battery);
+            ship.right_battery.set_current(saved.right_battery);
+            ship.crew.set_current(saved.crew);
+            ship.set_sails(saved.sails.clone());
+            ship.ammo = saved.ammo.clone();
+            ship.supplies = saved.supplies;
+            ship.cannons.set_reloading_for(
+                time.elapsed(),
+                CannonDirection::Left,
+                Duration::from_secs_f32(saved.left_cannons_reloading_for),
+                Duration::from_secs_f32(saved.left_cannons_reload_time),
+            );
+            ship.cannons.set_reloading_for(
+                time.elapsed(),
+                CannonDirection::Right,
+                Duration::from_secs_f32(saved.right_cannons_reloading_for),
+                Duration::from_secs_f32(saved.right_cannons_reload_time),
+            );
+
+            commands.entity(entity).insert((
+                Transform::from_translation(Vec3::from_array(saved.translation))
+                    .with_rotation(Quat::from_array(saved.rotation)),
+                Velocity {
+                    linvel: Vec3::from_array(saved.linvel),
+                    angvel: Vec3::from_array(saved.angvel),
+                },
+                ship,
+            ));
+        }
+        commands.remove_resource::<LoadedSave>();
+    }
+
+    /// Forgets a save that never made it to sea, so the next voyage starts fresh.
+    pub fn discard_save(mut commands: Commands) {
+        commands.remove_resource::<LoadedSave>();
+    }
+}
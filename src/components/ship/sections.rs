@@ -0,0 +1,90 @@
+use super::{cannons::CannonDirection, sinking::Sinking, Ship};
+use bevy::prelude::*;
+
+// Hull fraction below which the ship starts taking on water
+const FLOODING_HULL: f32 = 0.5;
+// Hull lost each second once the ship is as badly holed as it can be
+const FLOOD_RATE: f32 = 2.0;
+
+/// Part of a ship that can be damaged on its own.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Section {
+    // Sinks the ship when destroyed, and floods when badly holed
+    Hull,
+    // Limits how much sail the ship can carry
+    Masts,
+    // Slows the ship's turning
+    Rudder,
+    // Silences one side's cannons
+    Battery(CannonDirection),
+}
+
+// Points on the hull each section is hit around, as a fraction of the hull's half extents.
+// The ship's bow points along -z and its right side along +x.
+const SECTION_POINTS: [(Section, Vec3); 9] = [
+    (Section::Hull, Vec3::new(0.0, -0.6, -0.8)),
+    (Section::Hull, Vec3::new(-1.0, -0.6, 0.0)),
+    (Section::Hull, Vec3::new(1.0, -0.6, 0.0)),
+    (Section::Hull, Vec3::new(0.0, -0.6, 0.5)),
+    (Section::Masts, Vec3::new(0.0, 1.0, -0.5)),
+    (Section::Masts, Vec3::new(0.0, 1.0, 0.5)),
+    (Section::Rudder, Vec3::new(0.0, -0.4, 1.0)),
+    (
+        Section::Battery(CannonDirection::Left),
+        Vec3::new(-1.0, 0.0, 0.0),
+    ),
+    (
+        Section::Battery(CannonDirection::Right),
+        Vec3::new(1.0, 0.0, 0.0),
+    ),
+];
+
+impl Section {
+    pub const ALL: [Section; 5] = [
+        Section::Hull,
+        Section::Masts,
+        Section::Rudder,
+        Section::Battery(CannonDirection::Left),
+        Section::Battery(CannonDirection::Right),
+    ];
+
+    /// The section nearest to where a ship was hit.
+    pub fn hit(ship_transform: &Transform, half_extents: Vec3, point: Vec3) -> Self {
+        let local = ship_transform
+            .compute_affine()
+            .inverse()
+            .transform_point3(point)
+            / half_extents;
+        SECTION_POINTS
+            .iter()
+            .min_by(|(_, a), (_, b)| local.distance(*a).total_cmp(&local.distance(*b)))
+            .map(|(section, _)| *section)
+            .unwrap_or(Section::Hull)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Hull => "hull",
+            Self::Masts => "masts",
+            Self::Rudder => "rudder",
+            Self::Battery(CannonDirection::Left) => "port battery",
+            Self::Battery(CannonDirection::Right) => "starboard battery",
+        }
+    }
+}
+
+pub struct Systems;
+impl Systems {
+    /// Badly holed hulls take on water, wearing the hull down further the worse the damage.
+    pub fn flood(mut ships: Query<&mut Ship, Without<Sinking>>, time: Res<Time>) {
+        for mut ship in ships.iter_mut() {
+            let hull = ship.health.fraction();
+            if hull >= FLOODING_HULL {
+                continue;
+            }
+            let flooding = (FLOODING_HULL - hull) / FLOODING_HULL;
+            ship.health
+                .take_damage(FLOOD_RATE * flooding * time.delta_seconds());
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use super::{Ship, ShipMarker};
+use crate::components::id::Name;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Collider, RigidBody, Velocity};
+use bevy_water::WaterSettings;
+use std::f32::consts::FRAC_PI_4;
+
+// How long it takes a destroyed ship to slip beneath the waves
+const SINK_DURATION: f32 = 5.0;
+// How far below the water line the ship ends up before it's despawned
+const SINK_DEPTH: f32 = 30.0;
+// How far the ship rolls onto its side while sinking
+const SINK_ROLL: f32 = FRAC_PI_4;
+
+#[derive(Event)]
+pub struct ShipDestroyed {
+    pub entity: Entity,
+    pub marker: ShipMarker,
+}
+
+#[derive(Component)]
+pub struct Sinking {
+    timer: Timer,
+    start: Transform,
+}
+
+impl Sinking {
+    fn new(start: Transform) -> Self {
+        Self {
+            timer: Timer::from_seconds(SINK_DURATION, TimerMode::Once),
+            start,
+        }
+    }
+}
+
+pub struct Systems;
+impl Systems {
+    /// Starts sinking any ship whose health has run out.
+    /// Removing the ship's physics body stops it from sailing and from being hit again.
+    pub fn destroy_ships(
+        mut commands: Commands,
+        ships: Query<(Entity, &Ship, &ShipMarker, &Name, &Transform), Without<Sinking>>,
+        mut destroyed: EventWriter<ShipDestroyed>,
+    ) {
+        for (entity, ship, marker, name, transform) in ships.iter() {
+            if !ship.health.is_dead() {
+                continue;
+            }
+
+            println!("{} has been sunk! 🏴‍☠️", name.get());
+            commands
+                .entity(entity)
+                .remove::<(RigidBody, Collider, Velocity)>()
+                .insert(Sinking::new(*transform));
+            destroyed.send(ShipDestroyed {
+                entity,
+                marker: marker.clone(),
+            });
+        }
+    }
+
+    pub fn sink(
+        mut commands: Commands,
+        mut ships: Query<(Entity, &mut Transform, &mut Sinking)>,
+        water: Res<WaterSettings>,
+        time: Res<Time>,
+    ) {
+        for (entity, mut transform, mut sinking) in ships.iter_mut() {
+            sinking.timer.tick(time.delta());
+            let progress = sinking.timer.percent();
+
+            let target_height = water.height - SINK_DEPTH;
+            transform.translation.y = sinking.start.translation.y
+                + (target_height - sinking.start.translation.y) * progress;
+            transform.rotation =
+                sinking.start.rotation * Quat::from_rotation_z(SINK_ROLL * progress);
+
+            if sinking.timer.finished() {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_8};
+
+// How much gusts can add to or take away from the wind's base strength
+const GUST_STRENGTH: f32 = 0.3;
+// How far the wind can swing either side of its base direction, in radians
+const VEER_ANGLE: f32 = FRAC_PI_8;
+// Best speed, reached with the wind on the beam
+const BEAM_REACH_EFFICIENCY: f32 = 1.1;
+// Speed when running straight downwind
+const RUNNING_EFFICIENCY: f32 = 0.75;
+// Ships can still creep along when heading into the wind
+const IN_IRONS_EFFICIENCY: f32 = 0.1;
+
+#[derive(Resource)]
+pub struct Wind {
+    // Angle, about the y axis, the wind blows towards when it isn't veering
+    base_angle: f32,
+    base_strength: f32,
+    direction: Vec3,
+    strength: f32,
+}
+
+impl Default for Wind {
+    fn default() -> Self {
+        Self {
+            base_angle: 0.0,
+            base_strength: 1.0,
+            direction: Vec3::NEG_Z,
+            strength: 1.0,
+        }
+    }
+}
+
+impl Wind {
+    /// How well a ship with the given heading can use the wind, 1.0 being a normal day's sailing.
+    pub fn speed_factor(&self, ship_forward: Vec3) -> f32 {
+        let ship_forward = Vec3::new(ship_forward.x, 0.0, ship_forward.z).normalize_or_zero();
+        // 0 when running downwind, PI when heading straight into the wind
+        let angle = ship_forward.angle_between(self.direction);
+        let efficiency = if angle <= FRAC_PI_2 {
+            RUNNING_EFFICIENCY + (BEAM_REACH_EFFICIENCY - RUNNING_EFFICIENCY) * angle.sin()
+        } else {
+            (BEAM_REACH_EFFICIENCY * (angle - FRAC_PI_2).cos()).max(IN_IRONS_EFFICIENCY)
+        };
+        efficiency * self.strength
+    }
+}
+
+pub struct Systems;
+impl Systems {
+    /// Varies the wind over time. A few out of step waves keep the gusts from feeling regular.
+    pub fn gusts(mut wind: ResMut<Wind>, time: Res<Time>) {
+        let t = time.elapsed_seconds();
+        let gust = (t * 0.7).sin() * 0.6 + (t * 1.9).sin() * 0.3 + (t * 4.3).sin() * 0.1;
+        wind.strength = wind.base_strength * (1.0 + GUST_STRENGTH * gust);
+
+        let veer = (t * 0.05).sin() * 0.8 + (t * 0.13).sin() * 0.2;
+        let angle = wind.base_angle + VEER_ANGLE * veer;
+        wind.direction = Quat::from_rotation_y(angle) * Vec3::NEG_Z;
+    }
+}
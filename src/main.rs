@@ -5,7 +5,14 @@ use bevy_water::{ImageUtilsPlugin, WaterPlugin, WaterSettings};
 use std::time::Duration;
 
 mod components;
-use components::ship::{enemy, player};
+use components::controls::{self, Action, Actions};
+use components::faction;
+use components::session::{self, SessionEntity};
+use components::ship::{
+    ai, boarding, buoyancy, camera, cannons, class, enemy, fleet, player, ramming, repair, save,
+    sections, sinking,
+};
+use components::wind;
 mod ui;
 
 fn main() {
@@ -40,21 +47,192 @@ fn main() {
 pub struct GameMechanics;
 impl Plugin for GameMechanics {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, lights)
-            .add_systems(Startup, world)
-            .add_systems(Startup, player::Systems::spawn_ship)
-            .add_systems(Startup, enemy::Systems::spawn_ship)
+        app.add_event::<sinking::ShipDestroyed>()
+            .add_event::<cannons::CannonSplash>()
+            .add_event::<save::SaveRequest>()
+            .add_event::<boarding::ShipCaptured>()
+            .add_event::<cannons::KillingShot>()
+            .init_resource::<wind::Wind>()
+            .init_resource::<boarding::BoardingTarget>()
+            .init_resource::<fleet::Fleet>()
+            .init_resource::<fleet::FleetMenu>()
+            .init_resource::<player::Aim>()
+            .init_resource::<camera::CameraMode>()
+            .init_resource::<camera::KillCam>()
+            .add_asset::<class::ShipClass>()
+            .init_asset_loader::<class::ShipClassLoader>()
+            .add_systems(Update, class::Systems::hot_reload)
+            .add_systems(Startup, lights)
+            .add_systems(Startup, controls::Systems::load_controls)
+            .add_systems(Startup, faction::Systems::load_factions)
+            .add_systems(OnEnter(MenuState::MainMenu), session::Systems::clean_up)
+            .add_systems(OnEnter(MenuState::MainMenu), save::Systems::discard_save)
+            .add_systems(OnEnter(MenuState::Loading), session::Systems::clean_up)
+            .add_systems(OnEnter(MenuState::Loading), session::Systems::load_assets)
+            .add_systems(OnEnter(MenuState::Loading), fleet::Systems::reset_fleet)
+            .add_systems(
+                Update,
+                session::Systems::wait_for_assets
+                    .run_if(state_exists_and_equals(MenuState::Loading)),
+            )
+            .add_systems(
+                OnTransition {
+                    from: MenuState::Loading,
+                    to: MenuState::Ship,
+                },
+                (
+                    world,
+                    camera::Systems::spawn_camera,
+                    player::Systems::spawn_ship.run_if(not(resource_exists::<save::LoadedSave>())),
+                    enemy::Systems::spawn_ship.run_if(not(resource_exists::<save::LoadedSave>())),
+                    save::Systems::restore_game.run_if(resource_exists::<save::LoadedSave>()),
+                ),
+            )
+            .add_systems(
+                Update,
+                save::Systems::save_game.run_if(state_exists_and_equals(MenuState::Pause)),
+            )
+            .add_systems(
+                Update,
+                save::Systems::load_game.run_if(state_exists_and_equals(MenuState::Pause)),
+            )
+            .add_systems(OnEnter(MenuState::Pause), pause_game)
+            .add_systems(OnExit(MenuState::Pause), resume_game)
+            .add_systems(
+                Update,
+                wind::Systems::gusts.run_if(not(state_exists_and_equals(MenuState::Pause))),
+            )
             .add_systems(
                 Update,
                 player::Systems::movement.run_if(state_exists_and_equals(MenuState::Ship)),
             )
             .add_systems(
                 Update,
-                player::Systems::camera.run_if(state_exists_and_equals(MenuState::Ship)),
+                player::Systems::camera
+                    .after(player::Systems::aim)
+                    .run_if(state_exists_and_equals(MenuState::Ship)),
+            )
+            .add_systems(
+                Update,
+                camera::Systems::spectate
+                    .before(player::Systems::camera)
+                    .run_if(state_exists_and_equals(MenuState::Ship)),
+            )
+            .add_systems(
+                Update,
+                camera::Systems::record_kills
+                    .after(sinking::Systems::destroy_ships)
+                    .run_if(not(state_exists_and_equals(MenuState::Pause))),
+            )
+            .add_systems(
+                OnEnter(MenuState::GameOver),
+                camera::Systems::start_kill_cam,
+            )
+            .add_systems(OnEnter(MenuState::Victory), camera::Systems::start_kill_cam)
+            .add_systems(
+                Update,
+                camera::Systems::kill_cam.run_if(resource_equals(camera::CameraMode::KillCam)),
+            )
+            // The kill cam holds the game clock still while it replays the final shot
+            .add_systems(OnExit(MenuState::GameOver), resume_game)
+            .add_systems(OnExit(MenuState::Victory), resume_game)
+            .add_systems(
+                Update,
+                player::Systems::cannons
+                    .run_if(state_exists_and_equals(MenuState::Ship))
+                    .run_if(not(camera::spectating)),
+            )
+            .add_systems(
+                Update,
+                player::Systems::aim
+                    .before(player::Systems::cannons)
+                    .run_if(state_exists_and_equals(MenuState::Ship))
+                    .run_if(not(camera::spectating)),
+            )
+            .add_systems(
+                Update,
+                boarding::Systems::find_target.run_if(state_exists_and_equals(MenuState::Ship)),
+            )
+            .add_systems(
+                Update,
+                boarding::Systems::board
+                    .after(boarding::Systems::find_target)
+                    .before(session::Systems::end_session)
+                    .run_if(state_exists_and_equals(MenuState::Ship))
+                    .run_if(not(camera::spectating)),
+            )
+            .add_systems(
+                Update,
+                boarding::Systems::switch_command
+                    .run_if(state_exists_and_equals(MenuState::Ship))
+                    .run_if(not(camera::spectating)),
+            )
+            .add_systems(
+                Update,
+                fleet::Systems::command.run_if(state_exists_and_equals(MenuState::Ship)),
+            )
+            .add_systems(
+                Update,
+                fleet::Systems::keep_formation
+                    .after(fleet::Systems::command)
+                    .before(ai::Systems::behaviour)
+                    .run_if(state_exists_and_equals(MenuState::Ship)),
+            )
+            .add_systems(
+                Update,
+                ai::Systems::behaviour.run_if(state_exists_and_equals(MenuState::Ship)),
+            )
+            .add_systems(
+                Update,
+                buoyancy::Systems::float
+                    .after(player::Systems::movement)
+                    .after(ai::Systems::behaviour)
+                    .run_if(not(state_exists_and_equals(MenuState::Pause))),
+            )
+            .add_systems(
+                Update,
+                cannons::Systems::hit_detection
+                    .run_if(not(state_exists_and_equals(MenuState::Pause))),
+            )
+            .add_systems(
+                Update,
+                cannons::Systems::splash_detection
+                    .after(cannons::Systems::hit_detection)
+                    .run_if(not(state_exists_and_equals(MenuState::Pause))),
+            )
+            .add_systems(
+                Update,
+                ramming::Systems::ram_detection
+                    .run_if(not(state_exists_and_equals(MenuState::Pause))),
+            )
+            .add_systems(
+                Update,
+                repair::Systems::repair
+                    .after(cannons::Systems::hit_detection)
+                    .after(ramming::Systems::ram_detection)
+                    .run_if(not(state_exists_and_equals(MenuState::Pause))),
             )
             .add_systems(
                 Update,
-                player::Systems::cannons.run_if(state_exists_and_equals(MenuState::Ship)),
+                sections::Systems::flood.run_if(not(state_exists_and_equals(MenuState::Pause))),
+            )
+            .add_systems(
+                Update,
+                sinking::Systems::destroy_ships
+                    .after(cannons::Systems::hit_detection)
+                    .after(ramming::Systems::ram_detection)
+                    .after(sections::Systems::flood)
+                    .run_if(not(state_exists_and_equals(MenuState::Pause))),
+            )
+            .add_systems(
+                Update,
+                sinking::Systems::sink.run_if(not(state_exists_and_equals(MenuState::Pause))),
+            )
+            .add_systems(
+                Update,
+                session::Systems::end_session
+                    .after(sinking::Systems::destroy_ships)
+                    .run_if(state_exists_and_equals(MenuState::Ship)),
             );
     }
 }
@@ -62,63 +240,132 @@ impl Plugin for GameMechanics {
 pub struct Ui;
 impl Plugin for Ui {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, change_menu_state)
+        app.add_state::<ui::controls::ControlsMenu>()
+            .init_resource::<ui::controls::Rebinding>()
+            .init_resource::<ui::GamepadFocus>()
+            .add_systems(Startup, ui::spawn_ui_camera)
+            .add_systems(Update, change_menu_state)
+            .add_systems(Update, ui::button_interactions)
+            .add_systems(
+                Update,
+                ui::gamepad_navigation.after(ui::controls::capture_rebinding),
+            )
+            .add_systems(
+                Update,
+                ui::controls::capture_rebinding
+                    .before(change_menu_state)
+                    .before(ui::button_interactions),
+            )
+            .add_systems(
+                OnEnter(ui::controls::ControlsMenu::Open),
+                ui::controls::render_controls_menu,
+            )
+            .add_systems(
+                OnExit(ui::controls::ControlsMenu::Open),
+                ui::controls::close_controls_menu,
+            )
+            .add_systems(
+                Update,
+                ui::controls::update_controls_menu
+                    .run_if(state_exists_and_equals(ui::controls::ControlsMenu::Open)),
+            )
+            .add_systems(
+                OnExit(MenuState::MainMenu),
+                ui::controls::leave_controls_menu,
+            )
+            .add_systems(OnExit(MenuState::Pause), ui::controls::leave_controls_menu)
+            .add_systems(OnEnter(MenuState::MainMenu), ui::menu::render_main_menu)
+            .add_systems(OnExit(MenuState::MainMenu), ui::menu::close_menu_screen)
+            .add_systems(OnEnter(MenuState::Loading), ui::menu::render_loading_screen)
+            .add_systems(OnExit(MenuState::Loading), ui::menu::close_menu_screen)
+            .add_systems(
+                OnEnter(MenuState::GameOver),
+                ui::menu::render_game_over_screen,
+            )
+            .add_systems(OnExit(MenuState::GameOver), ui::menu::close_menu_screen)
+            .add_systems(OnEnter(MenuState::Victory), ui::menu::render_victory_screen)
+            .add_systems(OnExit(MenuState::Victory), ui::menu::close_menu_screen)
             .add_systems(OnEnter(MenuState::Pause), ui::pause::render_pause_menu)
             .add_systems(OnExit(MenuState::Pause), ui::pause::close_pause_menu)
+            .add_systems(OnEnter(MenuState::Ship), ui::ship::render_ship_hud)
+            .add_systems(OnExit(MenuState::Ship), ui::ship::close_ship_hud)
+            .add_systems(OnEnter(MenuState::Ship), ui::fleet::render_fleet_menu)
+            .add_systems(OnExit(MenuState::Ship), ui::fleet::close_fleet_menu)
             .add_systems(
                 Update,
-                ui::pause::pause_menu_interactions
-                    .run_if(state_exists_and_equals(MenuState::Pause)),
+                ui::fleet::update_fleet_menu.run_if(state_exists_and_equals(MenuState::Ship)),
+            )
+            .add_systems(
+                Update,
+                ui::ship::update_ship_hud.run_if(state_exists_and_equals(MenuState::Ship)),
             );
     }
 }
 
-#[derive(States, Debug, Hash, Eq, PartialEq, Clone)]
+#[derive(States, Debug, Hash, Eq, PartialEq, Clone, Default)]
 pub enum MenuState {
+    #[default]
+    MainMenu,
+    // Waiting on the ship model before setting sail
+    Loading,
     Pause,
     Ship,
-}
-
-impl Default for MenuState {
-    fn default() -> Self {
-        Self::Ship
-    }
+    GameOver,
+    Victory,
 }
 
 impl MenuState {
-    pub fn toggle_pause(current_state: &Self) -> Self {
+    /// Pausing is only possible while sailing, every other state is left as is.
+    pub fn toggle_pause(current_state: &Self) -> Option<Self> {
         match current_state {
-            Self::Pause => Self::Ship,
-            _ => Self::Pause,
+            Self::Pause => Some(Self::Ship),
+            Self::Ship => Some(Self::Pause),
+            _ => None,
         }
     }
 }
 
 pub fn change_menu_state(
-    keyboard: Res<Input<KeyCode>>,
+    actions: Actions,
     game_state: Res<State<MenuState>>,
     mut next_game_state: ResMut<NextState<MenuState>>,
 ) {
-    if keyboard.just_pressed(KeyCode::Escape) {
-        let updated_state = MenuState::toggle_pause(game_state.get());
+    if actions.just_pressed(Action::Pause) {
+        let Some(updated_state) = MenuState::toggle_pause(game_state.get()) else {
+            return;
+        };
         println!("Updated menu state 👉 {:?}", updated_state);
         next_game_state.set(updated_state);
     }
 }
 
+/// Freezes the game clock along with physics, so reload timers and projectiles pick up right where they left off.
+fn pause_game(mut time: ResMut<Time>, mut rapier: ResMut<RapierConfiguration>) {
+    time.pause();
+    rapier.physics_pipeline_active = false;
+}
+
+fn resume_game(mut time: ResMut<Time>, mut rapier: ResMut<RapierConfiguration>) {
+    time.unpause();
+    rapier.physics_pipeline_active = true;
+}
+
 fn world(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
-    commands.spawn(PbrBundle {
-        mesh: meshes.add(Mesh::from(shape::Plane {
-            size: 5.0,
-            subdivisions: 2,
-        })),
-        material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
-        ..default()
-    });
+    commands.spawn((
+        SessionEntity,
+        PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Plane {
+                size: 5.0,
+                subdivisions: 2,
+            })),
+            material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
+            ..default()
+        },
+    ));
 }
 
 fn lights(mut commands: Commands) {
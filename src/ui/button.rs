@@ -1,4 +1,9 @@
-use bevy::prelude::*;
+use super::controls::{ControlsMenu, Rebinding};
+use crate::{
+    components::{controls::Action, ship::save::SaveRequest},
+    MenuState,
+};
+use bevy::{app::AppExit, ecs::system::SystemParam, prelude::*};
 
 pub enum ButtonColors {
     Normal,
@@ -6,9 +11,9 @@ pub enum ButtonColors {
     Pressed,
 }
 
-impl Into<BackgroundColor> for ButtonColors {
-    fn into(self) -> BackgroundColor {
-        match self {
+impl From<ButtonColors> for BackgroundColor {
+    fn from(colors: ButtonColors) -> Self {
+        match colors {
             ButtonColors::Normal => Color::rgb(0.15, 0.15, 0.15).into(),
             ButtonColors::Hover => Color::rgb(0.25, 0.25, 0.25).into(),
             ButtonColors::Pressed => Color::rgb(0.75, 0.75, 0.75).into(),
@@ -18,10 +23,31 @@ impl Into<BackgroundColor> for ButtonColors {
 
 #[derive(Component)]
 pub enum ButtonMarker {
+    SetSail,
     Resume,
+    MainMenu,
+    Save,
+    Load,
+    Controls,
+    Rebind(Action),
+    CloseControls,
     Close,
 }
 
+impl ButtonMarker {
+    fn in_controls_menu(&self) -> bool {
+        matches!(self, ButtonMarker::Rebind(_) | ButtonMarker::CloseControls)
+    }
+}
+
+type ButtonInteraction<'a> = (&'a Interaction, &'a ButtonMarker, &'a mut BackgroundColor);
+type NavigableButton<'a> = (
+    Entity,
+    &'a ButtonMarker,
+    &'a GlobalTransform,
+    &'a mut BorderColor,
+);
+
 pub fn render_button_on_parent(
     parent: &mut ChildBuilder,
     marker: ButtonMarker,
@@ -69,3 +95,135 @@ pub fn render_text_on_parent(parent: &mut ChildBuilder, text: &'static str) {
         },
     ));
 }
+
+/// Everything a button can do once it's been clicked, or pressed with a gamepad.
+#[derive(SystemParam)]
+pub struct ButtonClicks<'w> {
+    next_game_state: ResMut<'w, NextState<MenuState>>,
+    next_controls_menu: ResMut<'w, NextState<ControlsMenu>>,
+    rebinding: ResMut<'w, Rebinding>,
+    save_requests: EventWriter<'w, SaveRequest>,
+    exit: EventWriter<'w, AppExit>,
+}
+
+impl<'w> ButtonClicks<'w> {
+    fn click(&mut self, marker: &ButtonMarker) {
+        match marker {
+            ButtonMarker::SetSail => {
+                self.next_game_state.set(MenuState::Loading);
+            }
+            ButtonMarker::Resume => {
+                self.next_game_state.set(MenuState::Ship);
+            }
+            ButtonMarker::MainMenu => {
+                self.next_game_state.set(MenuState::MainMenu);
+            }
+            ButtonMarker::Save => {
+                self.save_requests.send(SaveRequest::Save);
+            }
+            ButtonMarker::Load => {
+                self.save_requests.send(SaveRequest::Load);
+            }
+            ButtonMarker::Controls => {
+                self.next_controls_menu.set(ControlsMenu::Open);
+            }
+            ButtonMarker::Rebind(action) => {
+                self.rebinding.0 = Some(*action);
+            }
+            ButtonMarker::CloseControls => {
+                self.next_controls_menu.set(ControlsMenu::Closed);
+            }
+            ButtonMarker::Close => {
+                self.exit.send(AppExit);
+            }
+        };
+    }
+}
+
+pub fn button_interactions(
+    mut interaction_query: Query<ButtonInteraction, (Changed<Interaction>, With<Button>)>,
+    mut clicks: ButtonClicks,
+) {
+    for (interaction, marker, mut color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = ButtonColors::Pressed.into();
+                clicks.click(marker);
+            }
+            Interaction::Hovered => {
+                *color = ButtonColors::Hover.into();
+            }
+            Interaction::None => {
+                *color = ButtonColors::Normal.into();
+            }
+        }
+    }
+}
+
+/// Button the gamepad has moved onto, if the menus have been navigated with one.
+#[derive(Resource, Default)]
+pub struct GamepadFocus(Option<Entity>);
+
+/// Moves between buttons with the d-pad, top to bottom. South presses the focused button and East backs out.
+pub fn gamepad_navigation(
+    mut buttons: Query<NavigableButton, With<Button>>,
+    mut focus: ResMut<GamepadFocus>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    game_state: Res<State<MenuState>>,
+    controls_menu: Res<State<ControlsMenu>>,
+    mut clicks: ButtonClicks,
+) {
+    let just_pressed = |button_type| {
+        gamepads
+            .iter()
+            .any(|gamepad| gamepad_buttons.just_pressed(GamepadButton::new(gamepad, button_type)))
+    };
+
+    // Only the controls menu's buttons can be reached while it's open over another menu
+    let controls_open = *controls_menu.get() == ControlsMenu::Open;
+    let mut navigable: Vec<(Entity, Vec3)> = buttons
+        .iter()
+        .filter(|(_, marker, _, _)| marker.in_controls_menu() == controls_open)
+        .map(|(entity, _, transform, _)| (entity, transform.translation()))
+        .collect();
+    // UI y grows down the screen
+    navigable.sort_by(|a, b| a.1.y.total_cmp(&b.1.y).then(a.1.x.total_cmp(&b.1.x)));
+
+    let mut current = focus
+        .0
+        .and_then(|focused| navigable.iter().position(|(entity, _)| *entity == focused));
+    let down = just_pressed(GamepadButtonType::DPadDown);
+    if !navigable.is_empty() && (down || just_pressed(GamepadButtonType::DPadUp)) {
+        let count = navigable.len();
+        current = Some(match current {
+            Some(index) if down => (index + 1) % count,
+            Some(index) => (index + count - 1) % count,
+            None => 0,
+        });
+    }
+    focus.0 = current.map(|index| navigable[index].0);
+
+    if just_pressed(GamepadButtonType::South) {
+        if let Some((_, marker, _, _)) = focus.0.and_then(|focused| buttons.get(focused).ok()) {
+            clicks.click(marker);
+        }
+    } else if just_pressed(GamepadButtonType::East) {
+        if controls_open {
+            clicks.click(&ButtonMarker::CloseControls);
+        } else if *game_state.get() == MenuState::Pause {
+            clicks.click(&ButtonMarker::Resume);
+        }
+    }
+
+    for (entity, _, _, mut border) in buttons.iter_mut() {
+        let color = if focus.0 == Some(entity) {
+            Color::WHITE
+        } else {
+            Color::BLACK
+        };
+        if border.0 != color {
+            border.0 = color;
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use bevy::{prelude::*, ui::FocusPolicy};
+
+use super::button::{render_button_on_parent, render_text_on_parent, ButtonColors, ButtonMarker};
+use crate::components::controls::{Action, Binding, Controls};
+
+/// Opened on top of the main or pause menu, without leaving either.
+#[derive(States, Debug, Hash, Eq, PartialEq, Clone, Default)]
+pub enum ControlsMenu {
+    #[default]
+    Closed,
+    Open,
+}
+
+/// The action waiting on the player to press its new key or button, if any.
+#[derive(Resource, Default)]
+pub struct Rebinding(pub Option<Action>);
+
+#[derive(Component)]
+pub struct ControlsScreen;
+
+/// Text showing what is bound to an action.
+#[derive(Component)]
+pub struct BindingText(Action);
+
+fn bindings_label(controls: &Controls, action: Action) -> String {
+    let labels: Vec<String> = controls
+        .bindings(action)
+        .iter()
+        .map(|binding| binding.label())
+        .collect();
+    if labels.is_empty() {
+        "Unbound".to_string()
+    } else {
+        labels.join(", ")
+    }
+}
+
+fn render_binding_row(parent: &mut ChildBuilder, controls: &Controls, action: Action) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(520.0),
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                margin: UiRect::top(Val::Px(10.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                action.label(),
+                TextStyle {
+                    font_size: 30.0,
+                    color: Color::rgb(1.0, 1.0, 1.0),
+                    ..default()
+                },
+            ));
+            parent
+                .spawn((
+                    ButtonMarker::Rebind(action),
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(280.0),
+                            height: Val::Px(45.0),
+                            border: UiRect::all(Val::Px(3.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        border_color: BorderColor(Color::BLACK),
+                        background_color: ButtonColors::Normal.into(),
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        BindingText(action),
+                        TextBundle::from_section(
+                            bindings_label(controls, action),
+                            TextStyle {
+                                font_size: 24.0,
+                                color: Color::rgb(0.9, 0.9, 0.9),
+                                ..default()
+                            },
+                        ),
+                    ));
+                });
+        });
+}
+
+pub fn render_controls_menu(mut commands: Commands, controls: Res<Controls>) {
+    commands
+        .spawn((
+            ControlsScreen,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: Color::rgb(0.1, 0.25, 0.4).into(),
+                // Sits over the menu it was opened from, and keeps clicks from reaching that menu's buttons
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(1),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            render_text_on_parent(parent, "Controls");
+            // Rows run down the first column and carry on in the next, so every action fits on screen
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        height: Val::Px(610.0),
+                        flex_direction: FlexDirection::Column,
+                        flex_wrap: FlexWrap::Wrap,
+                        column_gap: Val::Px(40.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for action in Action::ALL {
+                        render_binding_row(parent, &controls, action);
+                    }
+                });
+            render_button_on_parent(parent, ButtonMarker::CloseControls, "Back");
+        });
+}
+
+pub fn close_controls_menu(
+    mut commands: Commands,
+    mut rebinding: ResMut<Rebinding>,
+    controls_screen: Query<Entity, With<ControlsScreen>>,
+) {
+    rebinding.0 = None;
+    for entity in controls_screen.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// The controls menu belongs to the menu it was opened from, so it goes when that menu does.
+pub fn leave_controls_menu(mut next_controls_menu: ResMut<NextState<ControlsMenu>>) {
+    next_controls_menu.set(ControlsMenu::Closed);
+}
+
+pub fn update_controls_menu(
+    controls: Res<Controls>,
+    rebinding: Res<Rebinding>,
+    mut texts: Query<(&mut Text, &BindingText)>,
+) {
+    if !controls.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+    for (mut text, binding_text) in texts.iter_mut() {
+        text.sections[0].value = if rebinding.0 == Some(binding_text.0) {
+            "Press a key or button...".to_string()
+        } else {
+            bindings_label(&controls, binding_text.0)
+        };
+    }
+}
+
+/// Binds the next key or button pressed to the action being rebound, and writes the new controls to disk.
+/// Escape or the pad's `East` button cancels instead. Either way the press is consumed, so it doesn't also pause the
+/// game or back out of the menu. Clicks on the menu's own buttons are left for the buttons.
+pub fn capture_rebinding(
+    mut rebinding: ResMut<Rebinding>,
+    mut controls: ResMut<Controls>,
+    mut keyboard: ResMut<Input<KeyCode>>,
+    mut mouse: ResMut<Input<MouseButton>>,
+    mut gamepad_buttons: ResMut<Input<GamepadButton>>,
+    buttons: Query<&Interaction, With<Button>>,
+) {
+    let Some(action) = rebinding.0 else {
+        return;
+    };
+
+    let key = keyboard.get_just_pressed().next().copied();
+    let over_button = buttons
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+    let mouse_button = mouse
+        .get_just_pressed()
+        .next()
+        .copied()
+        .filter(|_| !over_button);
+    let gamepad_button = gamepad_buttons.get_just_pressed().next().copied();
+
+    if key == Some(KeyCode::Escape)
+        || gamepad_button.is_some_and(|button| button.button_type == GamepadButtonType::East)
+    {
+        keyboard.clear_just_pressed(KeyCode::Escape);
+        if let Some(button) = gamepad_button {
+            gamepad_buttons.clear_just_pressed(button);
+        }
+        rebinding.0 = None;
+        return;
+    }
+
+    let binding = if let Some(key) = key {
+        keyboard.clear_just_pressed(key);
+        Binding::Key(key)
+    } else if let Some(button) = mouse_button {
+        mouse.clear_just_pressed(button);
+        Binding::Mouse(button)
+    } else if let Some(button) = gamepad_button {
+        gamepad_buttons.clear_just_pressed(button);
+        Binding::Gamepad(button.button_type)
+    } else {
+        return;
+    };
+
+    if controls.rebind(action, binding) {
+        controls.save();
+    } else {
+        println!("{} is kept for pausing the game", binding.label());
+    }
+    rebinding.0 = None;
+}
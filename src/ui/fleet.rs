@@ -0,0 +1,100 @@
+use crate::components::ship::fleet::{FleetCommand, FleetMenu};
+use bevy::prelude::*;
+
+// Distance from the centre of the screen to each command, in pixels
+const MENU_RADIUS: f32 = 160.0;
+const OPTION_SIZE: (f32, f32) = (190.0, 50.0);
+
+#[derive(Component)]
+pub struct FleetMenuScreen;
+
+#[derive(Component)]
+pub struct FleetMenuOption(FleetCommand);
+
+/// Spawned hidden, and shown while the fleet menu is held open.
+pub fn render_fleet_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            FleetMenuScreen,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            for (index, command) in FleetCommand::MENU.into_iter().enumerate() {
+                let position = FleetCommand::menu_direction(index) * MENU_RADIUS;
+                parent
+                    .spawn((
+                        FleetMenuOption(command),
+                        NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                left: Val::Percent(50.0),
+                                top: Val::Percent(50.0),
+                                width: Val::Px(OPTION_SIZE.0),
+                                height: Val::Px(OPTION_SIZE.1),
+                                // Centre the option on its place around the menu
+                                margin: UiRect {
+                                    left: Val::Px(position.x - OPTION_SIZE.0 / 2.0),
+                                    top: Val::Px(position.y - OPTION_SIZE.1 / 2.0),
+                                    ..default()
+                                },
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            command.label(),
+                            TextStyle {
+                                font_size: 26.0,
+                                color: Color::rgb(1.0, 1.0, 1.0),
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+pub fn close_fleet_menu(mut commands: Commands, screen: Query<Entity, With<FleetMenuScreen>>) {
+    for entity in screen.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn update_fleet_menu(
+    menu: Res<FleetMenu>,
+    mut screen: Query<&mut Visibility, With<FleetMenuScreen>>,
+    mut options: Query<(&FleetMenuOption, &mut BackgroundColor)>,
+) {
+    if !menu.is_changed() {
+        return;
+    }
+    for mut visibility in screen.iter_mut() {
+        *visibility = if menu.is_open() {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+    let selected = menu.selected();
+    for (option, mut background) in options.iter_mut() {
+        *background = if selected == Some(option.0) {
+            Color::rgba(0.9, 0.75, 0.3, 0.8).into()
+        } else {
+            Color::rgba(0.0, 0.0, 0.0, 0.6).into()
+        };
+    }
+}
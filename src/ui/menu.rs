@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+
+use super::button::{render_button_on_parent, render_text_on_parent, ButtonMarker};
+
+/// Root of whichever full screen menu is open, outside of the pause menu.
+#[derive(Component)]
+pub struct MenuScreen;
+
+fn render_menu_screen(commands: &mut Commands, children: impl FnOnce(&mut ChildBuilder)) {
+    commands
+        .spawn((
+            MenuScreen,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: Color::rgb(0.1, 0.25, 0.4).into(),
+                ..default()
+            },
+        ))
+        .with_children(children);
+}
+
+/// Shown once the voyage is over, along the bottom of the screen so the kill cam can be seen above it.
+fn render_end_screen(commands: &mut Commands, children: impl FnOnce(&mut ChildBuilder)) {
+    commands
+        .spawn((
+            MenuScreen,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::FlexEnd,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::bottom(Val::Px(40.0)),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(children);
+}
+
+pub fn render_main_menu(mut commands: Commands) {
+    render_menu_screen(&mut commands, |parent| {
+        render_text_on_parent(parent, "Open Ocean");
+        render_button_on_parent(parent, ButtonMarker::SetSail, "Set Sail");
+        render_button_on_parent(parent, ButtonMarker::Controls, "Controls");
+        render_button_on_parent(parent, ButtonMarker::Close, "Close");
+    });
+}
+
+pub fn render_loading_screen(mut commands: Commands) {
+    render_menu_screen(&mut commands, |parent| {
+        render_text_on_parent(parent, "Readying the fleet...");
+    });
+}
+
+pub fn render_game_over_screen(mut commands: Commands) {
+    render_end_screen(&mut commands, |parent| {
+        render_text_on_parent(parent, "Your ship has been sunk");
+        render_button_on_parent(parent, ButtonMarker::SetSail, "Try Again");
+        render_button_on_parent(parent, ButtonMarker::MainMenu, "Main Menu");
+    });
+}
+
+pub fn render_victory_screen(mut commands: Commands) {
+    render_end_screen(&mut commands, |parent| {
+        render_text_on_parent(parent, "Victory! The seas are yours");
+        render_button_on_parent(parent, ButtonMarker::SetSail, "Sail Again");
+        render_button_on_parent(parent, ButtonMarker::MainMenu, "Main Menu");
+    });
+}
+
+pub fn close_menu_screen(mut commands: Commands, menu: Query<Entity, With<MenuScreen>>) {
+    for entity in menu.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,3 +1,25 @@
+use bevy::{core_pipeline::clear_color::ClearColorConfig, prelude::*};
+
 mod button;
+pub mod controls;
+pub mod fleet;
+pub mod menu;
 pub mod pause;
 pub mod ship;
+
+pub use button::{button_interactions, gamepad_navigation, GamepadFocus};
+
+/// Draws the UI on top of whatever the ship camera has rendered.
+/// Kept separate from the ship camera so menus still show once the player's ship is gone.
+pub fn spawn_ui_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        camera: Camera {
+            order: 1,
+            ..default()
+        },
+        camera_2d: Camera2d {
+            clear_color: ClearColorConfig::None,
+        },
+        ..default()
+    });
+}
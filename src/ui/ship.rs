@@ -1 +1,195 @@
+use crate::components::{
+    id::Name,
+    ship::{
+        boarding::BoardingTarget, cannons::CannonDirection, fleet::Fleet, player::Aim,
+        sections::Section, PlayerShip, Ship,
+    },
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use std::time::Duration;
 
+#[derive(Component)]
+pub struct ShipHud;
+
+#[derive(Component)]
+pub enum HudField {
+    Health,
+    Masts,
+    Rudder,
+    Crew,
+    Sails,
+    Speed,
+    Heading,
+    LeftCannons,
+    RightCannons,
+    Ammo,
+    Repairs,
+    Boarding,
+    Fleet,
+    Aim,
+}
+
+pub fn render_ship_hud(mut commands: Commands) {
+    commands
+        .spawn((
+            ShipHud,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(20.0),
+                    bottom: Val::Px(20.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.4).into(),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            render_field_on_parent(parent, HudField::Health);
+            render_field_on_parent(parent, HudField::Masts);
+            render_field_on_parent(parent, HudField::Rudder);
+            render_field_on_parent(parent, HudField::Crew);
+            render_field_on_parent(parent, HudField::Sails);
+            render_field_on_parent(parent, HudField::Speed);
+            render_field_on_parent(parent, HudField::Heading);
+            render_field_on_parent(parent, HudField::LeftCannons);
+            render_field_on_parent(parent, HudField::RightCannons);
+            render_field_on_parent(parent, HudField::Ammo);
+            render_field_on_parent(parent, HudField::Repairs);
+            render_field_on_parent(parent, HudField::Boarding);
+            render_field_on_parent(parent, HudField::Fleet);
+            render_field_on_parent(parent, HudField::Aim);
+        });
+}
+
+fn render_field_on_parent(parent: &mut ChildBuilder, field: HudField) {
+    parent.spawn((
+        field,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 24.0,
+                color: Color::rgb(1.0, 1.0, 1.0),
+                ..Default::default()
+            },
+        ),
+    ));
+}
+
+pub fn close_ship_hud(mut commands: Commands, ship_hud: Query<Entity, With<ShipHud>>) {
+    for entity in ship_hud.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn update_ship_hud(
+    ship: Query<(&Ship, &Transform, &Velocity), With<PlayerShip>>,
+    mut fields: Query<(&HudField, &mut Text)>,
+    time: Res<Time>,
+    boarding_target: Res<BoardingTarget>,
+    fleet: Res<Fleet>,
+    aim: Res<Aim>,
+    names: Query<&Name>,
+) {
+    let Ok((ship, transform, velocity)) = ship.get_single() else {
+        return;
+    };
+
+    for (field, mut text) in fields.iter_mut() {
+        text.sections[0].value = match field {
+            HudField::Health => format!(
+                "Health: {:.0} / {:.0}",
+                ship.health().current(),
+                ship.health().max()
+            ),
+            HudField::Masts => section_status("Masts", ship, Section::Masts),
+            HudField::Rudder => section_status("Rudder", ship, Section::Rudder),
+            HudField::Crew => format!("Crew: {:.0}%", ship.crew().fraction() * 100.0),
+            HudField::Sails => format!(
+                "Sails: {} (max {})",
+                ship.sails().label(),
+                ship.max_sails().label()
+            ),
+            HudField::Speed => format!(
+                "Speed: {:.1}",
+                Vec3::new(velocity.linvel.x, 0.0, velocity.linvel.z).length()
+            ),
+            HudField::Heading => format!("Heading: {:03.0}°", heading(transform)),
+            HudField::LeftCannons => format!(
+                "Left cannons: {}",
+                reload_status(ship, time.elapsed(), CannonDirection::Left)
+            ),
+            HudField::RightCannons => format!(
+                "Right cannons: {}",
+                reload_status(ship, time.elapsed(), CannonDirection::Right)
+            ),
+            HudField::Ammo => {
+                let ammo = ship.ammo().selected();
+                format!("Ammo: {} ({})", ammo.label(), ship.ammo().remaining(ammo))
+            }
+            HudField::Repairs => {
+                if ship.is_repairing() {
+                    format!(
+                        "Repairing: {:.0}% ({:.0} supplies)",
+                        ship.condition() * 100.0,
+                        ship.supplies()
+                    )
+                } else {
+                    format!("Supplies: {:.0}", ship.supplies())
+                }
+            }
+            HudField::Boarding => match boarding_target.0.and_then(|target| names.get(target).ok())
+            {
+                Some(name) => format!("Alongside {}, ready to board", name.get()),
+                None => String::new(),
+            },
+            HudField::Fleet => match fleet.size() {
+                0 => String::new(),
+                1 => format!("Fleet: 1 ship, {}", fleet.label().to_lowercase()),
+                size => format!("Fleet: {} ships, {}", size, fleet.label().to_lowercase()),
+            },
+            HudField::Aim => match aim.side() {
+                Some(side) => format!(
+                    "Aiming {}: {:.0}° elevation, {:.0} m",
+                    match side {
+                        CannonDirection::Left => "left cannons",
+                        CannonDirection::Right => "right cannons",
+                    },
+                    aim.elevation().to_degrees(),
+                    aim.range()
+                ),
+                None => String::new(),
+            },
+        };
+    }
+}
+
+/// Compass heading in degrees, clockwise from the world's -z axis
+fn heading(transform: &Transform) -> f32 {
+    let forward = transform.forward();
+    forward.x.atan2(-forward.z).to_degrees().rem_euclid(360.0)
+}
+
+fn section_status(label: &str, ship: &Ship, section: Section) -> String {
+    format!(
+        "{}: {:.0}%",
+        label,
+        ship.section(section).fraction() * 100.0
+    )
+}
+
+fn reload_status(ship: &Ship, time_elapsed: Duration, direction: CannonDirection) -> String {
+    if ship.section(Section::Battery(direction)).is_dead() {
+        return "Out of action".to_string();
+    }
+    let progress = ship.cannons().reload_progress(time_elapsed, direction);
+    let elevation = ship.cannons().elevation(direction).to_degrees();
+    if progress >= 1.0 {
+        format!("Ready, {:.0}°", elevation)
+    } else {
+        format!("Reloading {:.0}%, {:.0}°", progress * 100.0, elevation)
+    }
+}